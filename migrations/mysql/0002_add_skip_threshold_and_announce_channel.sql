@@ -0,0 +1,3 @@
+ALTER TABLE guild_settings
+    ADD COLUMN skip_threshold BIGINT NOT NULL DEFAULT 50,
+    ADD COLUMN announce_channel_id BIGINT NOT NULL DEFAULT 0;
@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS favorites (
+    id BIGINT NOT NULL AUTO_INCREMENT PRIMARY KEY,
+    guild_id BIGINT NOT NULL,
+    added_by BIGINT NOT NULL,
+    track TEXT NOT NULL,
+    INDEX favorites_guild_id (guild_id)
+);
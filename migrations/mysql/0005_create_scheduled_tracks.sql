@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS scheduled_tracks (
+    id BIGINT NOT NULL AUTO_INCREMENT PRIMARY KEY,
+    guild_id BIGINT NOT NULL,
+    channel_id BIGINT NOT NULL,
+    requested_by BIGINT NOT NULL,
+    query TEXT NOT NULL,
+    play_at BIGINT NOT NULL,
+    INDEX scheduled_tracks_play_at (play_at)
+);
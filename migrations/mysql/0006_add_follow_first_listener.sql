@@ -0,0 +1 @@
+ALTER TABLE guild_settings ADD COLUMN follow_first_listener BOOLEAN NOT NULL DEFAULT FALSE;
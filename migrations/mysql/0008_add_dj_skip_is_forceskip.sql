@@ -0,0 +1 @@
+ALTER TABLE guild_settings ADD COLUMN dj_skip_is_forceskip BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,3 @@
+ALTER TABLE guild_settings
+    ADD COLUMN default_volume BIGINT NOT NULL DEFAULT 100,
+    ADD COLUMN max_volume BIGINT NOT NULL DEFAULT 1000;
@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS eq_profiles (
+    id BIGINT NOT NULL AUTO_INCREMENT PRIMARY KEY,
+    guild_id BIGINT NOT NULL,
+    name VARCHAR(100) NOT NULL,
+    filters TEXT NOT NULL,
+    UNIQUE INDEX eq_profiles_guild_id_name (guild_id, name)
+);
+
+ALTER TABLE guild_settings ADD COLUMN default_eq_profile_id BIGINT NOT NULL DEFAULT 0;
@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS guild_settings (
+    guild_id BIGINT NOT NULL PRIMARY KEY,
+    dj_id BIGINT NOT NULL DEFAULT 0,
+    dj_only BOOLEAN NOT NULL DEFAULT FALSE,
+    announce_songs BOOLEAN NOT NULL DEFAULT FALSE,
+    everyone_dj BOOLEAN NOT NULL DEFAULT FALSE
+);
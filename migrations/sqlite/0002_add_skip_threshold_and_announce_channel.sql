@@ -0,0 +1,2 @@
+ALTER TABLE guild_settings ADD COLUMN skip_threshold BIGINT NOT NULL DEFAULT 50;
+ALTER TABLE guild_settings ADD COLUMN announce_channel_id BIGINT NOT NULL DEFAULT 0;
@@ -0,0 +1 @@
+ALTER TABLE guild_settings ADD COLUMN auto_absent_cleanup BOOLEAN NOT NULL DEFAULT FALSE;
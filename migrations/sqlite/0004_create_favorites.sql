@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS favorites (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    guild_id BIGINT NOT NULL,
+    added_by BIGINT NOT NULL,
+    track TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS favorites_guild_id ON favorites (guild_id);
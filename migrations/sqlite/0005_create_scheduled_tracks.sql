@@ -0,0 +1,10 @@
+CREATE TABLE IF NOT EXISTS scheduled_tracks (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    guild_id BIGINT NOT NULL,
+    channel_id BIGINT NOT NULL,
+    requested_by BIGINT NOT NULL,
+    query TEXT NOT NULL,
+    play_at BIGINT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS scheduled_tracks_play_at ON scheduled_tracks (play_at);
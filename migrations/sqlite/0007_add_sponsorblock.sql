@@ -0,0 +1,2 @@
+ALTER TABLE guild_settings ADD COLUMN sponsorblock_categories BIGINT NOT NULL DEFAULT 0;
+ALTER TABLE guild_settings ADD COLUMN announce_sponsorblock BOOLEAN NOT NULL DEFAULT FALSE;
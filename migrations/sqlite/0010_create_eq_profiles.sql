@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS eq_profiles (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    guild_id BIGINT NOT NULL,
+    -- Compared regardless of case, like MySQL's default collation does
+    name TEXT NOT NULL COLLATE NOCASE,
+    filters TEXT NOT NULL
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS eq_profiles_guild_id_name ON eq_profiles (guild_id, name);
+
+ALTER TABLE guild_settings ADD COLUMN default_eq_profile_id BIGINT NOT NULL DEFAULT 0;
@@ -0,0 +1 @@
+ALTER TABLE guild_settings ADD COLUMN shuffle_playlists BOOLEAN NOT NULL DEFAULT FALSE;
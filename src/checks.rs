@@ -0,0 +1,65 @@
+use crate::{
+    responses::{self, Say},
+    Context, Error,
+};
+
+/// Returns true if the author of the command has DJ permissions in the guild.
+///
+/// Members that can manage the guild are always DJs, otherwise this depends on the `everyone_dj` and `dj_id` settings.
+pub async fn is_dj(context: Context<'_>) -> Result<bool, Error> {
+    let Some(guild) = context.guild() else {
+        return Ok(false);
+    };
+
+    let Some(member) = context.author_member().await else {
+        return Ok(false);
+    };
+
+    if guild.member_permissions(&member).manage_guild() {
+        return Ok(true);
+    }
+
+    let settings = context.data().settings.get(guild.id).await?;
+
+    if settings.everyone_dj {
+        return Ok(true);
+    }
+
+    Ok(settings.dj_role().is_some_and(|dj_role| member.roles.contains(&dj_role)))
+}
+
+/// Check for commands that may only be used by DJs.
+pub async fn dj(context: Context<'_>) -> Result<bool, Error> {
+    if is_dj(context).await? {
+        return Ok(true);
+    }
+
+    responses::ErrorMessage::UserNotDJ.say(context).await?;
+    Ok(false)
+}
+
+/// Check for commands that queue tracks, which are restricted to DJs while DJ only mode is enabled.
+pub async fn can_play(context: Context<'_>) -> Result<bool, Error> {
+    let guild_id = context.guild_id().unwrap();
+
+    let settings = context.data().settings.get(guild_id).await?;
+
+    if !settings.dj_only || is_dj(context).await? {
+        return Ok(true);
+    }
+
+    responses::ErrorMessage::DJOnlyMode.say(context).await?;
+    Ok(false)
+}
+
+/// Check for the play later commands, which are only available in the guilds listed in `PLAY_LATER_GUILDS`.
+pub async fn play_later(context: Context<'_>) -> Result<bool, Error> {
+    let guild_id = context.guild_id().unwrap();
+
+    if context.data().play_later_guilds.contains(&guild_id) {
+        return Ok(true);
+    }
+
+    responses::error(context, "Play later isn't enabled in this server.").await?;
+    Ok(false)
+}
@@ -0,0 +1,104 @@
+use std::collections::{HashSet, VecDeque};
+
+use lavalink_rs::player_context::{QueueMessage, TrackInQueue};
+use poise::serenity_prelude::{self as serenity, GuildId, UserId};
+use tracing::info;
+
+use crate::{
+    checks,
+    commands::music::{requester, voice_listeners},
+    responses::{self, Say},
+    Context, Data, Error,
+};
+
+/// Remove tracks queued by people that are no longer in the voice channel.
+#[poise::command(slash_command, rename = "absentcleanup", check = "checks::dj")]
+pub async fn absent_cleanup(context: Context<'_>) -> Result<(), Error> {
+    let guild = context.guild().unwrap();
+    let guild_id = guild.id;
+
+    let lava_client = context.data().lavalink.clone();
+
+    let Some(player_context) = lava_client.get_player_context(guild_id) else {
+        responses::ErrorMessage::BotNotInVC.say(context).await?;
+        return Ok(());
+    };
+
+    let bot_id = context.serenity_context().cache.current_user_id();
+    let Some((_, listeners)) = voice_listeners(&guild, bot_id) else {
+        responses::ErrorMessage::BotNotInVC.say(context).await?;
+        return Ok(());
+    };
+
+    let queue = player_context.get_queue().await?;
+    let (queue, removed) = split_absent(queue, &listeners);
+
+    if removed == 0 {
+        responses::default(context, "Every track in the queue was queued by someone in the voice channel.").await?;
+        return Ok(());
+    }
+
+    player_context.set_queue(QueueMessage::Replace(queue))?;
+
+    responses::default(
+        context,
+        format!("Removed **{}** track(s) queued by people that left the voice channel.", removed),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Called whenever someone's voice state changes, cleans up after people that left if the guild has it turned on.
+pub async fn automatic_cleanup(
+    context: &serenity::Context,
+    data: &Data,
+    guild_id: GuildId,
+) -> Result<(), Error> {
+    let Some(player_context) = data.lavalink.get_player_context(guild_id) else {
+        return Ok(());
+    };
+
+    if !data.settings.get(guild_id).await?.auto_absent_cleanup {
+        return Ok(());
+    }
+
+    let Some(guild) = context.cache.guild(guild_id) else {
+        return Ok(());
+    };
+
+    let Some((_, listeners)) = voice_listeners(&guild, context.cache.current_user_id()) else {
+        return Ok(());
+    };
+
+    let queue = player_context.get_queue().await?;
+    let (queue, removed) = split_absent(queue, &listeners);
+
+    if removed > 0 {
+        player_context.set_queue(QueueMessage::Replace(queue))?;
+        info!("Removed {} track(s) queued by absent listeners in {}", removed, guild_id);
+    }
+
+    Ok(())
+}
+
+/// Drops every track whose requester isn't listening, returning what's left of the queue and how many were dropped.
+///
+/// Tracks without a recorded requester are kept.
+fn split_absent(
+    queue: VecDeque<TrackInQueue>,
+    listeners: &HashSet<UserId>,
+) -> (VecDeque<TrackInQueue>, usize) {
+    let queue_length = queue.len();
+
+    let kept: VecDeque<TrackInQueue> = queue
+        .into_iter()
+        .filter(|wrapped_track| {
+            requester(&wrapped_track.track).map_or(true, |user_id| listeners.contains(&user_id))
+        })
+        .collect();
+
+    let removed = queue_length - kept.len();
+
+    (kept, removed)
+}
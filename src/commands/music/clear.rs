@@ -1,11 +1,11 @@
 use lavalink_rs::player_context;
 
 use crate::{
-    Context, Error, responses::{self, Say},
+    checks, Context, Error, responses::{self, Say},
 };
 
 /// Clear the queue, optionally inculding the currently playing audio.
-#[poise::command(slash_command)]
+#[poise::command(slash_command, check = "checks::dj")]
 pub async fn clear(
     context: Context<'_>,
     #[description = "Whether to stop and clear the currently playing audio."] now_playing: Option<bool>,
@@ -0,0 +1,210 @@
+use lavalink_rs::player_context::PlayerContext;
+use poise::serenity_prelude::GuildId;
+
+use crate::{
+    checks,
+    commands::{music::filter, settings},
+    database::{EqProfile, Setting},
+    responses::{self, Say},
+    Context, Data, Error,
+};
+
+/// The longest name a profile can have, which is what the database column fits.
+const MAX_NAME_LENGTH: usize = 100;
+
+// Discord doesn't permit invoking the root command of a slash command if it has subcommands, so the root command goes unused.
+#[poise::command(slash_command, subcommands("save", "load", "list", "delete", "default"))]
+pub async fn eq(_: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Save the filters that are currently applied as a profile, replacing the profile if it already exists
+#[poise::command(slash_command, check = "checks::dj")]
+async fn save(
+    context: Context<'_>,
+    #[description = "Name of the profile"] name: String,
+) -> Result<(), Error> {
+    let guild_id = context.guild_id().unwrap();
+    let name = name.trim();
+
+    if name.is_empty() || name.chars().count() > MAX_NAME_LENGTH {
+        responses::error(context, format!("Profile names have to be 1 to {} characters long.", MAX_NAME_LENGTH)).await?;
+        return Ok(());
+    }
+
+    let lava_client = context.data().lavalink.clone();
+
+    let Some(player_context) = lava_client.get_player_context(guild_id) else {
+        responses::ErrorMessage::BotNotInVC.say(context).await?;
+        return Ok(());
+    };
+
+    let filters = player_context.get_player().await?.filters.unwrap_or_default();
+
+    if filter::active_filters(&filters).is_empty() {
+        responses::error(context, "There are no filters applied to save.").await?;
+        return Ok(());
+    }
+
+    context
+        .data()
+        .database
+        .save_eq_profile(guild_id, name, &filters)
+        .await?;
+
+    responses::default(context, format!("Saved the current filters as **{}**.", name)).await?;
+
+    Ok(())
+}
+
+/// Apply a saved profile, replacing any filters that are already applied
+#[poise::command(slash_command, check = "checks::dj")]
+async fn load(
+    context: Context<'_>,
+    #[description = "Name of the profile"] name: String,
+) -> Result<(), Error> {
+    let guild_id = context.guild_id().unwrap();
+
+    let lava_client = context.data().lavalink.clone();
+
+    let Some(player_context) = lava_client.get_player_context(guild_id) else {
+        responses::ErrorMessage::BotNotInVC.say(context).await?;
+        return Ok(());
+    };
+
+    let Some(profile) = find_profile(context.data(), guild_id, &name).await? else {
+        responses::error(context, "There is no profile with that name.").await?;
+        return Ok(());
+    };
+
+    player_context.set_filters(profile.filters()?).await?;
+    responses::default(context, format!("Loaded the **{}** profile.", profile.name)).await?;
+
+    Ok(())
+}
+
+/// List the server's saved profiles
+#[poise::command(slash_command)]
+async fn list(context: Context<'_>) -> Result<(), Error> {
+    let guild_id = context.guild_id().unwrap();
+    let data = context.data();
+
+    let profiles = data.database.eq_profiles(guild_id).await?;
+
+    if profiles.is_empty() {
+        responses::default(context, "The server doesn't have any saved profiles yet.").await?;
+        return Ok(());
+    }
+
+    let default_id = data.settings.get(guild_id).await?.default_eq_profile_id;
+
+    let description = profiles
+        .iter()
+        .map(|profile| {
+            let filters = profile
+                .filters()
+                .map(|filters| filter::active_filters(&filters).join(", "))
+                .unwrap_or_else(|_| "Unreadable filters".to_owned());
+
+            match profile.id == default_id {
+                true => format!("**{}** (Default): {}", profile.name, filters),
+                false => format!("**{}**: {}", profile.name, filters),
+            }
+        })
+        .collect::<Vec<String>>()
+        .join("\n");
+
+    context
+        .send(|message| {
+            message.embed(|embed| {
+                embed
+                    .title("EQ Profiles")
+                    .description(description)
+                    .color(responses::Color::Default.to_color())
+            })
+        })
+        .await?;
+
+    Ok(())
+}
+
+/// Delete a saved profile
+#[poise::command(slash_command, check = "checks::dj")]
+async fn delete(
+    context: Context<'_>,
+    #[description = "Name of the profile"] name: String,
+) -> Result<(), Error> {
+    let guild_id = context.guild_id().unwrap();
+    let data = context.data();
+
+    let Some(profile) = find_profile(data, guild_id, &name).await? else {
+        responses::error(context, "There is no profile with that name.").await?;
+        return Ok(());
+    };
+
+    data.database.remove_eq_profile(guild_id, &profile.name).await?;
+
+    if data.settings.get(guild_id).await?.default_eq_profile_id == profile.id {
+        data.settings.update(guild_id, Setting::DefaultEqProfile(None)).await?;
+    }
+
+    responses::default(context, format!("Deleted the **{}** profile.", profile.name)).await?;
+
+    Ok(())
+}
+
+/// Set the profile applied whenever the bot joins a voice channel
+#[poise::command(slash_command, required_permissions = "MANAGE_GUILD")]
+async fn default(
+    context: Context<'_>,
+    #[description = "Name of the profile, leave empty to not apply one"] name: Option<String>,
+) -> Result<(), Error> {
+    set_default(context, name).await
+}
+
+/// Sets the guild's default profile by name, or clears it without one. Shared by `/eq default` and `/settings
+/// default_eq_profile`.
+pub async fn set_default(context: Context<'_>, name: Option<String>) -> Result<(), Error> {
+    let guild_id = context.guild_id().unwrap();
+
+    let profile_id = match name {
+        Some(name) => {
+            let Some(profile) = find_profile(context.data(), guild_id, &name).await? else {
+                responses::error(context, "There is no profile with that name.").await?;
+                return Ok(());
+            };
+
+            Some(profile.id)
+        }
+        None => None,
+    };
+
+    settings::update_default_eq_profile(context, profile_id).await
+}
+
+/// Applies the guild's default profile to a player the bot just created, if it has one.
+pub async fn apply_default(data: &Data, guild_id: GuildId, player_context: &PlayerContext) -> Result<(), Error> {
+    let default_id = data.settings.get(guild_id).await?.default_eq_profile_id;
+
+    if default_id == 0 {
+        return Ok(());
+    }
+
+    let profiles = data.database.eq_profiles(guild_id).await?;
+
+    if let Some(profile) = profiles.into_iter().find(|profile| profile.id == default_id) {
+        player_context.set_filters(profile.filters()?).await?;
+    }
+
+    Ok(())
+}
+
+/// Finds a guild's profile by name, ignoring case.
+async fn find_profile(data: &Data, guild_id: GuildId, name: &str) -> Result<Option<EqProfile>, Error> {
+    let name = name.trim();
+    let profiles = data.database.eq_profiles(guild_id).await?;
+
+    Ok(profiles
+        .into_iter()
+        .find(|profile| profile.name.eq_ignore_ascii_case(name)))
+}
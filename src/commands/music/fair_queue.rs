@@ -0,0 +1,135 @@
+use std::collections::{HashMap, VecDeque};
+
+use lavalink_rs::{model::track::TrackData, player_context::TrackInQueue};
+use poise::serenity_prelude::UserId;
+
+use crate::commands::music::requester;
+
+/// Inserts tracks into the queue so that requesters take turns, and returns the index the first one ended up at.
+///
+/// Every track in the queue belongs to a round, which is how many tracks its requester has ahead of it, counting the
+/// one currently playing. A new track goes in front of the first track of a later round than its own, so each round
+/// holds at most one track per requester and a long playlist only ever gets one track per round.
+pub fn insert_fairly(
+    queue: &mut VecDeque<TrackInQueue>,
+    tracks: VecDeque<TrackInQueue>,
+    currently_playing: Option<&TrackData>,
+) -> usize {
+    let mut first_index = None;
+
+    for track in tracks {
+        let index = insertion_index(queue, requester(&track.track), currently_playing);
+        queue.insert(index, track);
+        first_index.get_or_insert(index);
+    }
+
+    first_index.unwrap_or(queue.len())
+}
+
+fn insertion_index(
+    queue: &VecDeque<TrackInQueue>,
+    requester_id: Option<UserId>,
+    currently_playing: Option<&TrackData>,
+) -> usize {
+    let mut track_counts: HashMap<Option<UserId>, usize> = HashMap::new();
+
+    if let Some(track) = currently_playing {
+        track_counts.insert(requester(track), 1);
+    }
+
+    let rounds: Vec<usize> = queue
+        .iter()
+        .map(|queued| {
+            let count = track_counts.entry(requester(&queued.track)).or_default();
+            *count += 1;
+            *count - 1
+        })
+        .collect();
+
+    let round = track_counts.get(&requester_id).copied().unwrap_or(0);
+
+    rounds
+        .iter()
+        .position(|queued_round| *queued_round > round)
+        .unwrap_or(queue.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn track(title: &str, requester_id: u64) -> TrackData {
+        serde_json::from_value(serde_json::json!({
+            "encoded": title,
+            "info": {
+                "identifier": title,
+                "isSeekable": true,
+                "author": "Author",
+                "length": 180000,
+                "isStream": false,
+                "position": 0,
+                "title": title,
+                "uri": null,
+                "artworkUrl": null,
+                "isrc": null,
+                "sourceName": "http"
+            },
+            "pluginInfo": {},
+            "userData": { "requester_id": requester_id }
+        }))
+        .unwrap()
+    }
+
+    fn queue_of(tracks: &[(&str, u64)]) -> VecDeque<TrackInQueue> {
+        tracks
+            .iter()
+            .map(|(title, requester_id)| track(title, *requester_id).into())
+            .collect()
+    }
+
+    fn titles(queue: &VecDeque<TrackInQueue>) -> Vec<&str> {
+        queue.iter().map(|queued| queued.track.encoded.as_str()).collect()
+    }
+
+    #[test]
+    fn playlist_alternates_with_another_requester() {
+        let playing = track("b0", 2);
+        let mut queue = queue_of(&[("b1", 2)]);
+
+        let index = insert_fairly(&mut queue, queue_of(&[("a1", 1), ("a2", 1), ("a3", 1)]), Some(&playing));
+
+        assert_eq!(titles(&queue), ["a1", "b1", "a2", "a3"]);
+        assert_eq!(index, 0);
+    }
+
+    #[test]
+    fn nothing_playing() {
+        let mut queue = queue_of(&[("b1", 2), ("b2", 2)]);
+
+        let index = insert_fairly(&mut queue, queue_of(&[("a1", 1), ("a2", 1), ("a3", 1)]), None);
+
+        assert_eq!(titles(&queue), ["b1", "a1", "b2", "a2", "a3"]);
+        assert_eq!(index, 1);
+    }
+
+    #[test]
+    fn returns_index_of_first_inserted_track() {
+        let mut queue = queue_of(&[("a1", 1), ("a2", 1), ("a3", 1)]);
+
+        let index = insert_fairly(&mut queue, queue_of(&[("b1", 2), ("b2", 2)]), None);
+
+        assert_eq!(titles(&queue), ["a1", "b1", "a2", "b2", "a3"]);
+        assert_eq!(queue[index].track.encoded, "b1");
+    }
+
+    #[test]
+    fn currently_playing_counts_towards_its_requester() {
+        let playing = track("a0", 1);
+        let mut queue = queue_of(&[("a1", 1), ("a2", 1)]);
+
+        let index = insert_fairly(&mut queue, queue_of(&[("b1", 2)]), Some(&playing));
+
+        assert_eq!(titles(&queue), ["b1", "a1", "a2"]);
+        assert_eq!(index, 0);
+    }
+}
@@ -0,0 +1,237 @@
+use std::collections::VecDeque;
+
+use lavalink_rs::player_context::{QueueMessage, TrackInQueue};
+
+use crate::{
+    checks,
+    commands::music::{millis_to_string, play, set_requester, shuffle},
+    responses::{self, Say},
+    Context, Error,
+};
+
+#[derive(poise::ChoiceParameter, PartialEq)]
+pub enum PlayMode {
+    #[name = "Add to the queue"]
+    Append,
+    #[name = "Replace the queue"]
+    Replace,
+}
+
+// Discord doesn't permit invoking the root command of a slash command if it has subcommands, so the root command goes unused.
+#[poise::command(slash_command, subcommands("add", "list", "remove"))]
+pub async fn favorite(_: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Add the currently playing track, or a track in the queue, to the server's favorites.
+#[poise::command(slash_command, check = "checks::dj")]
+async fn add(
+    context: Context<'_>,
+    #[description = "The position of a track in the queue to add instead of the one currently playing."]
+    position: Option<usize>,
+) -> Result<(), Error> {
+    let guild_id = context.guild_id().unwrap();
+
+    let lava_client = context.data().lavalink.clone();
+
+    let Some(player_context) = lava_client.get_player_context(guild_id) else {
+        responses::ErrorMessage::BotNotInVC.say(context).await?;
+        return Ok(());
+    };
+
+    let Some(currently_playing) = player_context.get_player().await?.track else {
+        responses::ErrorMessage::BotNotPlaying.say(context).await?;
+        return Ok(());
+    };
+
+    let mut track = match position {
+        None => currently_playing,
+        Some(position) => {
+            let queue = player_context.get_queue().await?;
+
+            match position.checked_sub(1).and_then(|index| queue.get(index)) {
+                Some(wrapped_track) => wrapped_track.track.clone(),
+                None => {
+                    responses::error(context, "There is no track at the specified position.").await?;
+                    return Ok(());
+                }
+            }
+        }
+    };
+
+    // Whoever plays the favorite later becomes its requester
+    track.user_data = None;
+
+    context
+        .data()
+        .database
+        .add_favorite(guild_id, context.author().id, &track)
+        .await?;
+
+    match &track.info.uri {
+        Some(uri) => responses::default(context, format!("Added [{}]({}) to the server's favorites.", track.info.title, uri)).await?,
+        None => responses::default(context, format!("Added {} to the server's favorites.", track.info.title)).await?,
+    }
+
+    Ok(())
+}
+
+/// List the server's favorite tracks.
+#[poise::command(slash_command)]
+async fn list(
+    context: Context<'_>,
+    #[description = "The page of favorites that you would like to view."] page: Option<usize>,
+) -> Result<(), Error> {
+    let guild_id = context.guild_id().unwrap();
+    let favorites = context.data().database.favorites(guild_id).await?;
+
+    if favorites.is_empty() {
+        responses::default(context, "The server doesn't have any favorites yet.").await?;
+        return Ok(());
+    }
+
+    let page = page.unwrap_or(1);
+    let pages = (favorites.len() + 9) / 10;
+
+    if pages < page || page < 1 {
+        responses::error(context, "Invaild page specified.").await?;
+        return Ok(());
+    }
+
+    let mut favorites_string = String::default();
+
+    for (index, favorite) in favorites.iter().enumerate().skip((page - 1) * 10).take(10) {
+        let position = index + 1;
+
+        let Ok(track) = favorite.track() else {
+            favorites_string.push_str(&format!("**{}.** *Unreadable track*\n", position));
+            continue;
+        };
+
+        let duration = millis_to_string(track.info.length);
+
+        match &track.info.uri {
+            Some(uri) => favorites_string.push_str(&format!(
+                "**{}.** [{}]({}) `{}` - <@{}>\n",
+                position,
+                track.info.title,
+                uri,
+                duration,
+                favorite.added_by()
+            )),
+            None => favorites_string.push_str(&format!(
+                "**{}.** {} `{}` - <@{}>\n",
+                position,
+                track.info.title,
+                duration,
+                favorite.added_by()
+            )),
+        }
+    }
+
+    let footer = format!("Page {}/{} | {} favorite(s)", page, pages, favorites.len());
+
+    context
+        .send(|message| {
+            message.embed(|embed| {
+                embed
+                    .title("Favorites")
+                    .description(favorites_string)
+                    .footer(|embed_footer| embed_footer.text(footer))
+                    .color(responses::Color::Default.to_color())
+            })
+        })
+        .await?;
+
+    Ok(())
+}
+
+/// Remove a track from the server's favorites.
+#[poise::command(slash_command, check = "checks::dj")]
+async fn remove(
+    context: Context<'_>,
+    #[description = "The position of the track in the favorites list."] position: usize,
+) -> Result<(), Error> {
+    let guild_id = context.guild_id().unwrap();
+    let database = &context.data().database;
+
+    let favorites = database.favorites(guild_id).await?;
+
+    let Some(favorite) = position.checked_sub(1).and_then(|index| favorites.get(index)) else {
+        responses::error(context, "There is no favorite at the specified position.").await?;
+        return Ok(());
+    };
+
+    database.remove_favorite(guild_id, favorite.id).await?;
+
+    match favorite.track() {
+        Ok(track) => responses::default(context, format!("Removed {} from the server's favorites.", track.info.title)).await?,
+        Err(_) => responses::default(context, "Removed the track from the server's favorites.").await?,
+    }
+
+    Ok(())
+}
+
+/// Play the server's favorite tracks.
+#[poise::command(slash_command, rename = "playfavorites", check = "checks::dj")]
+pub async fn play_favorites(
+    context: Context<'_>,
+    #[description = "Whether to add the favorites to the queue or replace it. (Defaults to adding)"]
+    mode: Option<PlayMode>,
+    #[description = "Whether to shuffle the favorites."] shuffled: Option<bool>,
+) -> Result<(), Error> {
+    let guild_id = context.guild_id().unwrap();
+    let favorites = context.data().database.favorites(guild_id).await?;
+
+    let mut tracks: VecDeque<TrackInQueue> = favorites
+        .iter()
+        .filter_map(|favorite| favorite.track().ok())
+        .map(TrackInQueue::from)
+        .collect();
+
+    if tracks.is_empty() {
+        responses::error(context, "The server doesn't have any favorites yet.").await?;
+        return Ok(());
+    }
+
+    let Some(player_context) = play::join(context).await? else {
+        return Ok(());
+    };
+
+    let shuffled = shuffled.unwrap_or(false);
+    if shuffled {
+        tracks = shuffle::random_shuffle(tracks);
+    }
+
+    set_requester(&mut tracks, context.author().id);
+
+    let track_count = tracks.len();
+    let is_playing = player_context.get_player().await?.track.is_some();
+    let mode = mode.unwrap_or(PlayMode::Append);
+
+    let message = match (mode == PlayMode::Append, shuffled) {
+        (true, false) => format!("Queued **{}** favorite track(s).", track_count),
+        (true, true) => format!("Queued **{}** favorite track(s) in a random order.", track_count),
+        (false, false) => format!("Replaced the queue with **{}** favorite track(s).", track_count),
+        (false, true) => format!("Replaced the queue with **{}** favorite track(s) in a random order.", track_count),
+    };
+
+    responses::default(context, message).await?;
+
+    match mode {
+        PlayMode::Append => {
+            player_context.set_queue(QueueMessage::Append(tracks))?;
+
+            if !is_playing {
+                player_context.skip()?;
+            }
+        }
+        PlayMode::Replace => {
+            // Skipping the current track starts the favorites straight away
+            player_context.set_queue(QueueMessage::Replace(tracks))?;
+            player_context.skip()?;
+        }
+    }
+
+    Ok(())
+}
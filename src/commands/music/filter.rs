@@ -0,0 +1,243 @@
+use lavalink_rs::model::player::{Equalizer, Filters, Karaoke, LowPass, Rotation, Timescale};
+use poise::ChoiceParameter;
+
+use crate::{
+    checks,
+    responses::{self, Say},
+    Context, Error,
+};
+
+#[derive(poise::ChoiceParameter, Clone, Copy, PartialEq)]
+pub enum FilterPreset {
+    #[name = "Bass Boost"]
+    BassBoost,
+    #[name = "Nightcore"]
+    Nightcore,
+    #[name = "Vaporwave"]
+    Vaporwave,
+    #[name = "8D"]
+    EightD,
+    #[name = "Karaoke"]
+    Karaoke,
+}
+
+impl FilterPreset {
+    pub fn filters(&self) -> Filters {
+        use FilterPreset::*;
+        match self {
+            BassBoost => Filters {
+                equalizer: Some(bass_boost(0.2)),
+                ..Default::default()
+            },
+            Nightcore => Filters {
+                timescale: Some(timescale(1.25, 1.25)),
+                ..Default::default()
+            },
+            Vaporwave => Filters {
+                timescale: Some(timescale(0.8, 0.8)),
+                ..Default::default()
+            },
+            EightD => Filters {
+                rotation: Some(Rotation {
+                    rotation_hz: Some(0.2),
+                }),
+                ..Default::default()
+            },
+            Karaoke => Filters {
+                karaoke: Some(Karaoke {
+                    level: Some(1.0),
+                    mono_level: Some(1.0),
+                    filter_band: Some(220.0),
+                    filter_width: Some(100.0),
+                }),
+                ..Default::default()
+            },
+        }
+    }
+}
+
+/// Boosts the lowest bands of the equalizer, each one a bit less than the one below it.
+fn bass_boost(gain: f64) -> Vec<Equalizer> {
+    (0..4)
+        .map(|band| Equalizer {
+            band,
+            gain: gain * (4 - band) as f64 / 4.0,
+        })
+        .collect()
+}
+
+fn timescale(speed: f64, pitch: f64) -> Timescale {
+    Timescale {
+        speed: Some(speed),
+        pitch: Some(pitch),
+        rate: Some(1.0),
+    }
+}
+
+/// Returns the names of the kinds of filters that are turned on.
+pub fn active_filters(filters: &Filters) -> Vec<&'static str> {
+    let mut active = Vec::new();
+
+    if filters.equalizer.as_ref().is_some_and(|bands| !bands.is_empty()) {
+        active.push("Equalizer");
+    }
+
+    if filters.timescale.is_some() {
+        active.push("Timescale");
+    }
+
+    if filters.rotation.is_some() {
+        active.push("Rotation");
+    }
+
+    if filters.karaoke.is_some() {
+        active.push("Karaoke");
+    }
+
+    if filters.low_pass.is_some() {
+        active.push("Low Pass");
+    }
+
+    active
+}
+
+/// Returns how much faster than normal the player is playing tracks, which is 1 without a timescale filter.
+pub fn playback_speed(filters: Option<&Filters>) -> f64 {
+    let Some(timescale) = filters.and_then(|filters| filters.timescale.as_ref()) else {
+        return 1.0;
+    };
+
+    let speed = timescale.speed.unwrap_or(1.0) * timescale.rate.unwrap_or(1.0);
+
+    if speed > 0.0 {
+        speed
+    } else {
+        1.0
+    }
+}
+
+// Discord doesn't permit invoking the root command of a slash command if it has subcommands, so the root command goes unused.
+#[poise::command(slash_command, subcommands("preset", "custom", "clear"))]
+pub async fn filter(_: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Apply a filter preset, replacing any filters that are already applied
+#[poise::command(slash_command, check = "checks::dj")]
+async fn preset(
+    context: Context<'_>,
+    #[description = "The preset to apply"] preset: FilterPreset,
+) -> Result<(), Error> {
+    let guild_id = context.guild_id().unwrap();
+
+    let lava_client = context.data().lavalink.clone();
+
+    let Some(player_context) = lava_client.get_player_context(guild_id) else {
+        responses::ErrorMessage::BotNotInVC.say(context).await?;
+        return Ok(());
+    };
+
+    player_context.set_filters(preset.filters()).await?;
+    responses::default(context, format!("Applied the **{}** filter.", preset.name())).await?;
+
+    Ok(())
+}
+
+/// Adjust individual filters, keeping the ones that are already applied
+#[poise::command(slash_command, check = "checks::dj")]
+async fn custom(
+    context: Context<'_>,
+    #[description = "Playback speed, 1 is normal"]
+    #[min = 0.25]
+    #[max = 3.0]
+    speed: Option<f64>,
+    #[description = "Pitch, 1 is normal"]
+    #[min = 0.25]
+    #[max = 3.0]
+    pitch: Option<f64>,
+    #[description = "How many times per second the audio rotates around the listener, 0 turns it off"]
+    #[min = 0.0]
+    #[max = 5.0]
+    rotation: Option<f64>,
+    #[description = "How much the bass is boosted, 0 turns it off"]
+    #[min = 0.0]
+    #[max = 1.0]
+    bass: Option<f64>,
+    #[description = "How strongly high frequencies are muffled, from 1 to 100, 1 or less turns it off"]
+    #[min = 0.0]
+    #[max = 100.0]
+    low_pass: Option<f64>,
+) -> Result<(), Error> {
+    let guild_id = context.guild_id().unwrap();
+
+    let lava_client = context.data().lavalink.clone();
+
+    let Some(player_context) = lava_client.get_player_context(guild_id) else {
+        responses::ErrorMessage::BotNotInVC.say(context).await?;
+        return Ok(());
+    };
+
+    if [speed, pitch, rotation, bass, low_pass].iter().all(Option::is_none) {
+        responses::error(context, "Specify at least one filter to adjust.").await?;
+        return Ok(());
+    }
+
+    let mut filters = player_context.get_player().await?.filters.unwrap_or_default();
+
+    if speed.is_some() || pitch.is_some() {
+        let current = filters.timescale.take();
+        let current_speed = current.as_ref().and_then(|timescale| timescale.speed);
+        let current_pitch = current.as_ref().and_then(|timescale| timescale.pitch);
+
+        let speed = speed.or(current_speed).unwrap_or(1.0);
+        let pitch = pitch.or(current_pitch).unwrap_or(1.0);
+
+        // Back to normal is the same as no timescale at all
+        filters.timescale = (speed != 1.0 || pitch != 1.0).then(|| timescale(speed, pitch));
+    }
+
+    if let Some(rotation) = rotation {
+        filters.rotation = (rotation > 0.0).then_some(Rotation {
+            rotation_hz: Some(rotation),
+        });
+    }
+
+    if let Some(bass) = bass {
+        filters.equalizer = (bass > 0.0).then(|| bass_boost(bass));
+    }
+
+    // Lavalink ignores smoothing of 1 or less, so that's treated the same as no low pass at all
+    if let Some(low_pass) = low_pass {
+        filters.low_pass = (low_pass > 1.0).then_some(LowPass {
+            smoothing: Some(low_pass),
+        });
+    }
+
+    let active = active_filters(&filters);
+    player_context.set_filters(filters).await?;
+
+    match active.is_empty() {
+        true => responses::default(context, "Turned off every filter.").await?,
+        false => responses::default(context, format!("Active filters: **{}**", active.join(", "))).await?,
+    }
+
+    Ok(())
+}
+
+/// Turn off every filter
+#[poise::command(slash_command, check = "checks::dj")]
+async fn clear(context: Context<'_>) -> Result<(), Error> {
+    let guild_id = context.guild_id().unwrap();
+
+    let lava_client = context.data().lavalink.clone();
+
+    let Some(player_context) = lava_client.get_player_context(guild_id) else {
+        responses::ErrorMessage::BotNotInVC.say(context).await?;
+        return Ok(());
+    };
+
+    player_context.set_filters(Filters::default()).await?;
+    responses::default(context, "Turned off every filter.").await?;
+
+    Ok(())
+}
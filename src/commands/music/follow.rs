@@ -0,0 +1,67 @@
+use poise::serenity_prelude::{self as serenity, VoiceState};
+
+use crate::{commands::music::voice_listeners, Data, Error};
+
+/// Called whenever someone else's voice state changes, moves the bot along with the listener it follows if the guild
+/// has it turned on.
+///
+/// The bot follows whoever summoned it, or otherwise the first person to join its channel, until they leave voice
+/// entirely. Moving the call makes Discord send a new voice server, which carries the player over to the new channel.
+pub async fn follow_first_listener(
+    context: &serenity::Context,
+    data: &Data,
+    voice_state: &VoiceState,
+) -> Result<(), Error> {
+    let Some(guild_id) = voice_state.guild_id else {
+        return Ok(());
+    };
+
+    if voice_state.member.as_ref().is_some_and(|member| member.user.bot) {
+        return Ok(());
+    }
+
+    if data.lavalink.get_player_context(guild_id).is_none() {
+        return Ok(());
+    }
+
+    if !data.settings.get(guild_id).await?.follow_first_listener {
+        return Ok(());
+    }
+
+    let Some(guild) = context.cache.guild(guild_id) else {
+        return Ok(());
+    };
+
+    let Some((bot_channel_id, _)) = voice_listeners(&guild, context.cache.current_user_id()) else {
+        return Ok(());
+    };
+
+    let followed = data.followed_listeners.lock().unwrap().get(&guild_id).copied();
+
+    match followed {
+        Some(user_id) if user_id == voice_state.user_id => match voice_state.channel_id {
+            Some(channel_id) if channel_id != bot_channel_id => {
+                let manager = songbird::get(context).await.unwrap().clone();
+
+                let (_, handler) = manager.join_gateway(guild_id, channel_id).await;
+                handler?;
+            }
+            Some(_) => (),
+            // They left voice, so the next person to join the bot's channel gets followed instead
+            None => {
+                data.followed_listeners.lock().unwrap().remove(&guild_id);
+            }
+        },
+        Some(_) => (),
+        None => {
+            if voice_state.channel_id == Some(bot_channel_id) {
+                data.followed_listeners
+                    .lock()
+                    .unwrap()
+                    .insert(guild_id, voice_state.user_id);
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -1,17 +1,23 @@
 use lavalink_rs::player_context::QueueMessage;
 
 use crate::{
+    checks,
     responses::{self, Say},
     Context, Error,
 };
 
 /// Skip a track without voting
-#[poise::command(slash_command, rename = "forceskip")]
+#[poise::command(slash_command, rename = "forceskip", check = "checks::dj")]
 pub async fn force_skip(
     context: Context<'_>,
     #[description = "The position in the queue to skip to"] 
     position: Option<usize>,
 ) -> Result<(), Error> {
+    skip_without_voting(context, position).await
+}
+
+/// Skips the current track, or to a position in the queue, without checking whether the author is a DJ.
+pub async fn skip_without_voting(context: Context<'_>, position: Option<usize>) -> Result<(), Error> {
     let guild = context.guild().unwrap();
     let guild_id = guild.id;
 
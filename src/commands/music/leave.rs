@@ -1,9 +1,9 @@
 use crate::{
-    Context, Error, responses::{self, Say},
+    checks, Context, Error, responses::{self, Say},
 };
 
 /// Disconnect the bot from the current channel.
-#[poise::command(slash_command)]
+#[poise::command(slash_command, check = "checks::dj")]
 pub async fn leave(
     context: Context<'_>,
 ) -> Result<(), Error> {
@@ -24,6 +24,7 @@ pub async fn leave(
 
     manager.leave(guild_id).await?;
     lava_client.delete_player(guild_id).await?;
+    context.data().loop_modes.lock().unwrap().remove(&guild_id);
 
     responses::default(context, format!("Left the voice channel.")).await?;
 
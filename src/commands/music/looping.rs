@@ -0,0 +1,116 @@
+use lavalink_rs::{
+    model::track::TrackData,
+    player_context::{PlayerContext, QueueMessage},
+};
+use poise::serenity_prelude::GuildId;
+
+use crate::{
+    checks,
+    responses::{self, Say},
+    Context, Data, Error,
+};
+
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub enum LoopMode {
+    #[default]
+    Off,
+    Track,
+    Queue,
+}
+
+impl LoopMode {
+    pub fn name(&self) -> &'static str {
+        use LoopMode::*;
+        match self {
+            Off => "Off",
+            Track => "Track",
+            Queue => "Queue",
+        }
+    }
+}
+
+/// Returns the loop mode of a guild.
+pub fn loop_mode(data: &Data, guild_id: GuildId) -> LoopMode {
+    data.loop_modes
+        .lock()
+        .unwrap()
+        .get(&guild_id)
+        .copied()
+        .unwrap_or_default()
+}
+
+/// Called whenever a track finishes playing.
+///
+/// Looping works by queueing the track again once it's over, at the front of the queue when looping the track and at
+/// the back when looping the queue, so that nothing extra sits in the queue while the track is playing.
+///
+/// The player has already moved on to the next track in the queue by the time this runs, or stopped if there wasn't
+/// one. So a looped track replaces whatever was started, which goes back to the front of the queue, and a looped queue
+/// is started again if it had run out.
+pub async fn requeue(
+    data: &Data,
+    guild_id: GuildId,
+    player_context: &PlayerContext,
+    track: &TrackData,
+) -> Result<(), Error> {
+    match loop_mode(data, guild_id) {
+        LoopMode::Off => (),
+        LoopMode::Track => {
+            if let Some(started) = player_context.get_player().await?.track {
+                player_context.set_queue(QueueMessage::PushToFront(started.into()))?;
+            }
+
+            player_context.play_now(track).await?;
+        }
+        LoopMode::Queue => {
+            player_context.set_queue(QueueMessage::PushToBack(track.clone().into()))?;
+
+            if player_context.get_player().await?.track.is_none() {
+                player_context.skip()?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Loop the currently playing track
+#[poise::command(slash_command, rename = "loop", check = "checks::dj")]
+pub async fn loop_track(context: Context<'_>) -> Result<(), Error> {
+    toggle(context, LoopMode::Track).await
+}
+
+/// Loop the entire queue
+#[poise::command(slash_command, rename = "loopqueue", check = "checks::dj")]
+pub async fn loop_queue(context: Context<'_>) -> Result<(), Error> {
+    toggle(context, LoopMode::Queue).await
+}
+
+/// Switches the guild to the given loop mode, or turns looping off if it's already in it.
+async fn toggle(context: Context<'_>, mode: LoopMode) -> Result<(), Error> {
+    let guild_id = context.guild_id().unwrap();
+    let data = context.data();
+
+    let lava_client = data.lavalink.clone();
+
+    if lava_client.get_player_context(guild_id).is_none() {
+        responses::ErrorMessage::BotNotInVC.say(context).await?;
+        return Ok(());
+    }
+
+    let new_mode = if loop_mode(data, guild_id) == mode {
+        LoopMode::Off
+    } else {
+        mode
+    };
+
+    data.loop_modes.lock().unwrap().insert(guild_id, new_mode);
+
+    match new_mode {
+        LoopMode::Off => responses::default(context, "Stopped looping.").await?,
+        LoopMode::Track => responses::default(context, "Now looping the current track.").await?,
+        LoopMode::Queue => responses::default(context, "Now looping the queue.").await?,
+    }
+
+    Ok(())
+}
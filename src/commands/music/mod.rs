@@ -1,10 +1,162 @@
+pub mod absent_cleanup;
 pub mod clear;
+pub mod eq;
+pub mod fair_queue;
+pub mod favorites;
+pub mod filter;
+pub mod follow;
 pub mod force_skip;
 pub mod leave;
+pub mod looping;
+pub mod now_playing;
+pub mod pause;
 pub mod play;
+pub mod play_later;
 pub mod queue;
 pub mod remove;
+pub mod remove_dupes;
 pub mod reorder;
+pub mod seek;
+pub mod shuffle;
+pub mod skip;
+pub mod sponsorblock;
+pub mod stop;
+pub mod volume;
+
+use std::collections::{HashSet, VecDeque};
+
+use lavalink_rs::{
+    model::track::TrackData,
+    player_context::{PlayerContext, QueueMessage, TrackInQueue},
+};
+use poise::serenity_prelude::{ChannelId, Guild, UserId};
+
+use crate::Error;
+
+/// Records the user that requested a set of tracks in each track's user data.
+fn set_requester(tracks: &mut VecDeque<TrackInQueue>, user_id: UserId) {
+    for track in tracks {
+        track.track.user_data = Some(serde_json::json!({ "requester_id": user_id.0 }));
+    }
+}
+
+/// Returns the user that requested a track, if one was recorded when it was queued.
+fn requester(track: &TrackData) -> Option<UserId> {
+    track.user_data.as_ref()?["requester_id"].as_u64().map(UserId)
+}
+
+/// Rearranges the queue as it is right now, replacing it with the result unless `rearrange` returns `None`.
+///
+/// The queue can only be replaced as a whole, so anything that happens to it between fetching and replacing it is undone,
+/// like a track that started in the meantime playing again or a track queued in the meantime disappearing. Nothing is
+/// awaited in between, which is why the queue is fetched here instead of being passed in.
+async fn rearrange_queue<T>(
+    player_context: &PlayerContext,
+    rearrange: impl FnOnce(&mut VecDeque<TrackInQueue>) -> Option<T>,
+) -> Result<Option<T>, Error> {
+    let mut queue = player_context.get_queue().await?;
+
+    let Some(result) = rearrange(&mut queue) else {
+        return Ok(None);
+    };
+
+    player_context.set_queue(QueueMessage::Replace(queue))?;
+
+    Ok(Some(result))
+}
+
+/// Returns the voice channel the bot is connected to in the guild, along with every non-bot user listening in it.
+fn voice_listeners(guild: &Guild, bot_id: UserId) -> Option<(ChannelId, HashSet<UserId>)> {
+    let channel_id = guild.voice_states.get(&bot_id)?.channel_id?;
+
+    let listeners = guild
+        .voice_states
+        .values()
+        .filter(|voice_state| voice_state.channel_id == Some(channel_id))
+        .filter(|voice_state| voice_state.user_id != bot_id)
+        .filter(|voice_state| !voice_state.member.as_ref().is_some_and(|member| member.user.bot))
+        .map(|voice_state| voice_state.user_id)
+        .collect();
+
+    Some((channel_id, listeners))
+}
+
+/// Parses a duration written either like a timestamp, such as `1:23` or `01:02:03`, or with units, such as `90s` or
+/// `1m30s`. The inverse of `millis_to_string`, for durations shorter than a day.
+///
+/// With `units_only`, timestamps and numbers without a unit are rejected, for delays where `1:00` or `5` would be
+/// ambiguous.
+fn string_to_millis(input: &str, units_only: bool) -> Option<u64> {
+    let input = input.trim();
+
+    if input.is_empty() {
+        return None;
+    }
+
+    if input.contains(':') {
+        if units_only {
+            return None;
+        }
+
+        let parts: Vec<&str> = input.split(':').collect();
+
+        if parts.len() > 3 {
+            return None;
+        }
+
+        let mut seconds: u64 = 0;
+        for part in parts {
+            seconds = seconds.checked_mul(60)?.checked_add(part.parse().ok()?)?;
+        }
+
+        return seconds.checked_mul(1000);
+    }
+
+    let mut seconds: u64 = 0;
+    let mut number = String::new();
+
+    for character in input.chars() {
+        if character.is_ascii_digit() {
+            number.push(character);
+            continue;
+        }
+
+        let unit = match character.to_ascii_lowercase() {
+            'h' => 60 * 60,
+            'm' => 60,
+            's' => 1,
+            _ => return None,
+        };
+
+        let amount: u64 = number.parse().ok()?;
+        seconds = seconds.checked_add(amount.checked_mul(unit)?)?;
+        number.clear();
+    }
+
+    // A plain number is taken as seconds
+    if !number.is_empty() {
+        if units_only {
+            return None;
+        }
+
+        seconds = seconds.checked_add(number.parse().ok()?)?;
+    }
+
+    seconds.checked_mul(1000)
+}
+
+/// Converts a duration in a track to how long it takes to play at the given speed, see `filter::playback_speed`.
+///
+/// Lengths and positions of the queue and the current track are shown, and seek positions are entered, in this time,
+/// so they match how long things actually take while a timescale filter is applied.
+fn millis_at_speed(millis: u64, speed: f64) -> u64 {
+    (millis as f64 / speed) as u64
+}
+
+/// The inverse of `millis_at_speed`, converts how long something takes to play at the given speed to a duration in the track.
+fn millis_in_track(millis: u64, speed: f64) -> u64 {
+    (millis as f64 * speed) as u64
+}
 
 fn millis_to_string(millis: u64) -> String {
     let seconds = (millis / 1000) % 60;
@@ -0,0 +1,216 @@
+use std::time::Duration;
+
+use lavalink_rs::{model::player::Player, player_context::PlayerContext};
+use poise::serenity_prelude::{ChannelId, CreateEmbed, GuildId};
+
+use crate::{
+    commands::music::{filter, looping::{self, LoopMode}, millis_at_speed, millis_to_string, requester},
+    database::GuildSettings,
+    responses::{self, Say},
+    Context, Data, Error,
+};
+
+/// How often the now playing message is refreshed.
+const UPDATE_INTERVAL: Duration = Duration::from_secs(10);
+const PROGRESS_BAR_LENGTH: usize = 20;
+
+/// Show the currently playing track.
+#[poise::command(slash_command, rename = "nowplaying")]
+pub async fn now_playing(context: Context<'_>) -> Result<(), Error> {
+    let guild = context.guild().unwrap();
+    let guild_id = guild.id;
+
+    let lava_client = context.data().lavalink.clone();
+
+    let Some(player_context) = lava_client.get_player_context(guild_id) else {
+        responses::ErrorMessage::BotNotInVC.say(context).await?;
+        return Ok(());
+    };
+
+    let player = player_context.get_player().await?;
+
+    let Some(track) = player.track.clone() else {
+        responses::ErrorMessage::BotNotPlaying.say(context).await?;
+        return Ok(());
+    };
+
+    let loop_mode = looping::loop_mode(context.data(), guild_id);
+
+    let reply = context
+        .send(|message| message.embed(|embed| now_playing_embed(embed, &player, loop_mode)))
+        .await?;
+
+    let mut message = reply.message().await?.into_owned();
+
+    // Only the most recent now playing message in a guild is kept up to date
+    let data = context.data().clone();
+    data.now_playing_messages
+        .lock()
+        .unwrap()
+        .insert(guild_id, message.id);
+
+    let http = context.serenity_context().http.clone();
+
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(UPDATE_INTERVAL).await;
+
+            if data.now_playing_messages.lock().unwrap().get(&guild_id) != Some(&message.id) {
+                break;
+            }
+
+            let Some(player_context) = lava_client.get_player_context(guild_id) else {
+                break;
+            };
+
+            let Ok(player) = player_context.get_player().await else {
+                break;
+            };
+
+            // Stop once the track has ended
+            if !player
+                .track
+                .as_ref()
+                .is_some_and(|current| current.encoded == track.encoded)
+            {
+                break;
+            }
+
+            let loop_mode = looping::loop_mode(&data, guild_id);
+
+            if message
+                .edit(&http, |message| {
+                    message.embed(|embed| now_playing_embed(embed, &player, loop_mode))
+                })
+                .await
+                .is_err()
+            {
+                break;
+            }
+        }
+
+        let mut now_playing_messages = data.now_playing_messages.lock().unwrap();
+        if now_playing_messages.get(&guild_id) == Some(&message.id) {
+            now_playing_messages.remove(&guild_id);
+        }
+    });
+
+    Ok(())
+}
+
+/// Called whenever a track starts playing, posts a now playing message if the guild has song announcements enabled.
+///
+/// Announcements go to the configured announcement channel, or the channel the bot was summoned from if there isn't
+/// one. The previous announcement is deleted so only the current track's is left.
+pub async fn announce(
+    data: &Data,
+    guild_id: GuildId,
+    player_context: &PlayerContext,
+) -> Result<(), Error> {
+    let settings = data.settings.get(guild_id).await?;
+
+    if !settings.announce_songs {
+        return Ok(());
+    }
+
+    let Some(channel_id) = announcement_channel(data, guild_id, &settings) else {
+        return Ok(());
+    };
+
+    let previous = data.announcements.lock().unwrap().remove(&guild_id);
+    if let Some((previous_channel_id, message_id)) = previous {
+        // Someone may have already deleted it
+        previous_channel_id.delete_message(&data.http, message_id).await.ok();
+    }
+
+    let player = player_context.get_player().await?;
+    let loop_mode = looping::loop_mode(data, guild_id);
+
+    let message = channel_id
+        .send_message(&data.http, |message| {
+            message.embed(|embed| now_playing_embed(embed, &player, loop_mode))
+        })
+        .await?;
+
+    data.announcements
+        .lock()
+        .unwrap()
+        .insert(guild_id, (channel_id, message.id));
+
+    Ok(())
+}
+
+/// Returns the channel announcements are sent to, which is the configured announcement channel or the channel the bot
+/// was summoned from if there isn't one.
+pub fn announcement_channel(data: &Data, guild_id: GuildId, settings: &GuildSettings) -> Option<ChannelId> {
+    let summon_channel_id = data.summon_channels.lock().unwrap().get(&guild_id).copied();
+    settings.announce_channel().or(summon_channel_id)
+}
+
+/// Builds an embed describing the track the player is currently playing.
+pub fn now_playing_embed<'a>(
+    embed: &'a mut CreateEmbed,
+    player: &Player,
+    loop_mode: LoopMode,
+) -> &'a mut CreateEmbed {
+    embed.color(responses::Color::Default.to_color());
+
+    let Some(track) = &player.track else {
+        return embed.description("Nothing is currently playing.");
+    };
+
+    embed.title(&track.info.title).field("Author", &track.info.author, true);
+
+    if let Some(uri) = &track.info.uri {
+        embed.url(uri);
+    }
+
+    if let Some(artwork_url) = &track.info.artwork_url {
+        embed.thumbnail(artwork_url);
+    }
+
+    if let Some(user_id) = requester(track) {
+        embed.field("Requested by", format!("<@{}>", user_id), true);
+    }
+
+    let progress = if track.info.is_stream {
+        "🔴 Live".to_string()
+    } else {
+        let speed = filter::playback_speed(player.filters.as_ref());
+
+        format!(
+            "`{}` {} `{}`",
+            millis_to_string(millis_at_speed(player.state.position, speed)),
+            progress_bar(player.state.position, track.info.length),
+            millis_to_string(millis_at_speed(track.info.length, speed)),
+        )
+    };
+
+    embed
+        .description(progress)
+        .footer(|footer| {
+            let mut text = format!("Volume: {}% | Looping: {}", player.volume, loop_mode.name());
+
+            if player.paused {
+                text.push_str(" | Paused");
+            }
+
+            footer.text(text)
+        })
+}
+
+fn progress_bar(position: u64, length: u64) -> String {
+    let filled = if length == 0 {
+        0
+    } else {
+        (position.min(length) * PROGRESS_BAR_LENGTH as u64 / length) as usize
+    };
+
+    let filled = filled.min(PROGRESS_BAR_LENGTH - 1);
+
+    format!(
+        "{}🔘{}",
+        "▬".repeat(filled),
+        "▬".repeat(PROGRESS_BAR_LENGTH - 1 - filled)
+    )
+}
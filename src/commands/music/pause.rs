@@ -0,0 +1,76 @@
+use crate::{
+    checks,
+    responses::{self, Say},
+    Context, Error,
+};
+
+/// Pause the currently playing track
+#[poise::command(slash_command, check = "checks::dj")]
+pub async fn pause(context: Context<'_>) -> Result<(), Error> {
+    let guild_id = context.guild_id().unwrap();
+
+    let lava_client = context.data().lavalink.clone();
+
+    let Some(player_context) = lava_client.get_player_context(guild_id) else {
+        responses::ErrorMessage::BotNotInVC.say(context).await?;
+        return Ok(());
+    };
+
+    let player = player_context.get_player().await?;
+
+    if player.track.is_none() {
+        responses::ErrorMessage::BotNotPlaying.say(context).await?;
+        return Ok(());
+    }
+
+    if player.paused {
+        responses::ErrorMessage::AlreadyPaused.say(context).await?;
+        return Ok(());
+    }
+
+    player_context.set_pause(true).await?;
+    responses::DefaultMessage::Paused.say(context).await?;
+
+    Ok(())
+}
+
+/// Resume playback after pausing or stopping
+#[poise::command(slash_command, check = "checks::dj")]
+pub async fn resume(context: Context<'_>) -> Result<(), Error> {
+    let guild_id = context.guild_id().unwrap();
+
+    let lava_client = context.data().lavalink.clone();
+
+    let Some(player_context) = lava_client.get_player_context(guild_id) else {
+        responses::ErrorMessage::BotNotInVC.say(context).await?;
+        return Ok(());
+    };
+
+    let player = player_context.get_player().await?;
+
+    if player.track.is_some() {
+        if !player.paused {
+            responses::ErrorMessage::NotPaused.say(context).await?;
+            return Ok(());
+        }
+
+        player_context.set_pause(false).await?;
+        responses::DefaultMessage::Resumed.say(context).await?;
+        return Ok(());
+    }
+
+    // After /stop nothing is playing, but the queue is still there to pick back up
+    if player_context.get_queue().await?.is_empty() {
+        responses::ErrorMessage::BotNotPlaying.say(context).await?;
+        return Ok(());
+    }
+
+    if player.paused {
+        player_context.set_pause(false).await?;
+    }
+
+    player_context.skip()?;
+    responses::DefaultMessage::Resumed.say(context).await?;
+
+    Ok(())
+}
@@ -1,38 +1,76 @@
 use std::collections::VecDeque;
 
 use crate::{
-    commands::music::millis_to_string,
+    checks,
+    commands::music::{eq, fair_queue, filter, millis_at_speed, millis_to_string, rearrange_queue, set_requester, shuffle, sponsorblock},
     responses::{self, Say},
-    Context, Error,
+    Context, Data, Error,
 };
 use lavalink_rs::{
-    model::track::PlaylistInfo,
+    model::track::{PlaylistInfo, Track},
     player_context::{PlayerContext, QueueMessage},
-    prelude::{SearchEngines, TrackInQueue, TrackLoadData},
+    prelude::{LavalinkClient, SearchEngines, TrackInQueue, TrackLoadData},
 };
+use poise::serenity_prelude::GuildId;
+use tracing::error;
 
 /// Play a song in the voice channel you are connected to.
-#[poise::command(slash_command)]
+#[poise::command(slash_command, check = "checks::can_play")]
 pub async fn play(
     context: Context<'_>,
     #[description = "Search term or URL"] query: String,
 ) -> Result<(), Error> {
-    let Some((player_context, tracks, playlist_info, playlist_count)) =
+    let Some((player_context, mut tracks, playlist_info, playlist_count)) =
         connect_and_get_tracks(context, &query).await?
     else {
         return Ok(());
     };
 
-    let track = &tracks[0].track;
-    let track_length = millis_to_string(track.info.length);
+    let guild_id = context.guild_id().unwrap();
+    let settings = context.data().settings.get(guild_id).await?;
+
+    let shuffled = playlist_info.is_some() && settings.shuffle_playlists;
+
+    if shuffled {
+        tracks = shuffle::random_shuffle(tracks);
+    }
+
+    let player = player_context.get_player().await?;
+    let speed = filter::playback_speed(player.filters.as_ref());
+    let currently_playing = player.track;
+
+    let track = tracks[0].track.clone();
+    let track_length = millis_to_string(millis_at_speed(track.info.length, speed));
 
-    let queue_length = if player_context.get_player().await?.track.is_some() {
-        player_context.get_queue().await?.len() + 1
+    let position = if settings.fair_queue {
+        let index = rearrange_queue(&player_context, |queue| {
+            Some(fair_queue::insert_fairly(queue, tracks, currently_playing.as_ref()))
+        })
+        .await?
+        .unwrap();
+
+        index + 1
+    } else {
+        let position = player_context.get_queue().await?.len() + 1;
+        player_context.set_queue(QueueMessage::Append(tracks))?;
+        position
+    };
+
+    // When nothing is playing, the front of the queue is started below, which moves every track up by one. That's only
+    // the new track if the queue was empty, since a stopped player keeps its queue
+    let queue_length = if currently_playing.is_some() {
+        position
     } else {
-        0
+        position - 1
     };
 
     let message = match (playlist_info, queue_length, &track.info.uri) {
+        (Some(info), _, _) if shuffled => format!(
+            "Queued **{}** tracks from playlist: [{}]({}) in a random order",
+            playlist_count.unwrap(),
+            info.name,
+            query
+        ),
         (Some(info), _, _) => format!(
             "Queued **{}** tracks from playlist: [{}]({})",
             playlist_count.unwrap(),
@@ -71,8 +109,6 @@ pub async fn play(
 
     responses::default(context, message).await?;
 
-    player_context.set_queue(QueueMessage::Append(tracks))?;
-
     // We need to skip if there's nothing currently playing? I got this from the example
     if let Ok(player_data) = player_context.get_player().await {
         if player_data.track.is_none()
@@ -89,7 +125,7 @@ pub async fn play(
 }
 
 /// Replace the currently playing song with the provided one
-#[poise::command(slash_command, rename = "playskip")]
+#[poise::command(slash_command, rename = "playskip", check = "checks::dj")]
 pub async fn play_skip(
     context: Context<'_>,
     #[description = "Search term or URL"] query: String,
@@ -100,8 +136,10 @@ pub async fn play_skip(
         return Ok(());
     };
 
+    let speed = filter::playback_speed(player_context.get_player().await?.filters.as_ref());
+
     let track = &tracks[0].track;
-    let track_length = millis_to_string(track.info.length);
+    let track_length = millis_to_string(millis_at_speed(track.info.length, speed));
 
     let message = match (playlist_info, &track.info.uri) {
         (Some(info), _) => format!(
@@ -136,18 +174,76 @@ pub async fn play_skip(
     Ok(())
 }
 
-async fn connect_and_get_tracks(
+/// Add a song to the top of the queue, so that it plays next
+#[poise::command(slash_command, rename = "playtop", check = "checks::dj")]
+pub async fn play_top(
     context: Context<'_>,
-    query: &String,
-) -> Result<
-    Option<(
-        PlayerContext,
-        VecDeque<TrackInQueue>,
-        Option<PlaylistInfo>,
-        Option<usize>,
-    )>,
-    Error,
-> {
+    #[description = "Search term or URL"] query: String,
+) -> Result<(), Error> {
+    let Some((player_context, mut tracks, playlist_info, playlist_count)) =
+        connect_and_get_tracks(context, &query).await?
+    else {
+        return Ok(());
+    };
+
+    let player = player_context.get_player().await?;
+    let speed = filter::playback_speed(player.filters.as_ref());
+    let is_playing = player.track.is_some();
+
+    let track = &tracks[0].track;
+    let track_length = millis_to_string(millis_at_speed(track.info.length, speed));
+
+    let message = match (playlist_info, is_playing, &track.info.uri) {
+        (Some(info), _, _) => format!(
+            "Queued **{}** tracks from playlist: [{}]({}) up next",
+            playlist_count.unwrap(),
+            info.name,
+            query
+        ),
+        (None, false, Some(uri)) => format!(
+            "Started playing `{}` [{}]({}) - <@{}>",
+            track_length,
+            track.info.title,
+            uri,
+            context.author().id
+        ),
+        (None, false, None) => format!(
+            "Started playing `{}` {} - <@{}>",
+            track_length,
+            track.info.title,
+            context.author().id
+        ),
+        (None, true, Some(uri)) => format!(
+            "Up next `{}` [{}]({}) - <@{}>",
+            track_length,
+            track.info.title,
+            uri,
+            context.author().id
+        ),
+        (None, true, None) => format!(
+            "Up next `{}` {} - <@{}>",
+            track_length,
+            track.info.title,
+            context.author().id
+        ),
+    };
+
+    responses::default(context, message).await?;
+
+    let queue = player_context.get_queue().await?;
+    tracks.extend(queue);
+    player_context.set_queue(QueueMessage::Replace(tracks))?;
+
+    if !is_playing {
+        player_context.skip()?;
+    }
+
+    Ok(())
+}
+
+/// Joins the voice channel of the author of the command if the bot isn't connected yet, and returns the guild's
+/// player. Returns `None` if that isn't possible, after telling the author why.
+pub async fn join(context: Context<'_>) -> Result<Option<PlayerContext>, Error> {
     let guild = context.guild().unwrap();
     let guild_id = guild.id;
 
@@ -180,9 +276,27 @@ async fn connect_and_get_tracks(
 
         match handler {
             Ok(connection_info) => {
-                lava_client
+                let player_context = lava_client
                     .create_player_context(guild_id, connection_info)
                     .await?;
+
+                setup_player(context.data(), guild_id, &player_context).await?;
+
+                // Remember where the bot was summoned from so songs can be announced there
+                context
+                    .data()
+                    .summon_channels
+                    .lock()
+                    .unwrap()
+                    .insert(guild_id, context.channel_id());
+
+                // Whoever summoned the bot is the first listener it follows between channels
+                context
+                    .data()
+                    .followed_listeners
+                    .lock()
+                    .unwrap()
+                    .insert(guild_id, context.author().id);
             }
             Err(why) => {
                 responses::ErrorMessage::BotCannotJoinVC(why)
@@ -199,18 +313,69 @@ async fn connect_and_get_tracks(
         return Ok(None);
     };
 
+    Ok(Some(player_context))
+}
+
+/// Applies the guild's settings to a player the bot just created.
+pub async fn setup_player(data: &Data, guild_id: GuildId, player_context: &PlayerContext) -> Result<(), Error> {
+    let settings = data.settings.get(guild_id).await?;
+
+    player_context
+        .set_volume(settings.default_volume.min(settings.max_volume) as u16)
+        .await?;
+
+    // Playing works without the default profile or the plugin, so neither is worth failing over
+    if let Err(why) = eq::apply_default(data, guild_id, player_context).await {
+        error!("Could not apply the default EQ profile: {}", why);
+    }
+
+    if let Err(why) = sponsorblock::apply(data, guild_id).await {
+        error!("Could not set SponsorBlock categories: {}", why);
+    }
+
+    Ok(())
+}
+
+/// Loads the tracks a url points to, or searches YouTube if the query isn't a url.
+pub async fn load_tracks(
+    lava_client: &LavalinkClient,
+    guild_id: GuildId,
+    query: &str,
+) -> Result<Track, Error> {
     let query = if query.starts_with("http") {
-        query.clone()
+        query.to_owned()
     } else {
         SearchEngines::YouTube.to_query(query)?
     };
 
-    let loaded_tracks = lava_client.load_tracks(guild_id, &query).await?;
+    Ok(lava_client.load_tracks(guild_id, &query).await?)
+}
+
+async fn connect_and_get_tracks(
+    context: Context<'_>,
+    query: &String,
+) -> Result<
+    Option<(
+        PlayerContext,
+        VecDeque<TrackInQueue>,
+        Option<PlaylistInfo>,
+        Option<usize>,
+    )>,
+    Error,
+> {
+    let guild_id = context.guild_id().unwrap();
+    let lava_client = context.data().lavalink.clone();
+
+    let Some(player_context) = join(context).await? else {
+        return Ok(None);
+    };
+
+    let loaded_tracks = load_tracks(&lava_client, guild_id, query).await?;
 
     let mut playlist_info = None;
     let mut playlist_count = None;
 
-    let tracks: VecDeque<TrackInQueue> = match loaded_tracks.data {
+    let mut tracks: VecDeque<TrackInQueue> = match loaded_tracks.data {
         Some(TrackLoadData::Track(track)) => VecDeque::from([track.into()]),
         Some(TrackLoadData::Search(search_results)) => {
             VecDeque::from([search_results[0].clone().into()])
@@ -232,6 +397,8 @@ async fn connect_and_get_tracks(
         }
     };
 
+    set_requester(&mut tracks, context.author().id);
+
     Ok(Some((
         player_context,
         tracks,
@@ -0,0 +1,326 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use lavalink_rs::{
+    player_context::{PlayerContext, QueueMessage, TrackInQueue},
+    prelude::TrackLoadData,
+};
+use poise::serenity_prelude::{self as serenity, ChannelId, Guild, UserId};
+use tracing::{error, info, warn};
+
+use crate::{
+    checks,
+    commands::music::{play, set_requester, string_to_millis},
+    database::ScheduledTrack,
+    responses::{self, Say},
+    Context, Data, Error,
+};
+
+/// How often the scheduler looks for tracks that are due.
+const POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+// Discord doesn't permit invoking the root command of a slash command if it has subcommands, so the root command goes unused.
+#[poise::command(slash_command, rename = "playlater", subcommands("add", "list", "cancel"))]
+pub async fn play_later(_: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Queue a song at a later time, joining the most populated voice channel if the bot isn't in one.
+#[poise::command(slash_command, check = "checks::play_later", check = "checks::can_play")]
+async fn add(
+    context: Context<'_>,
+    #[description = "Search term or URL"] query: String,
+    #[description = "A delay like 1h30m, or a Discord timestamp like <t:1700000000>"] time: String,
+) -> Result<(), Error> {
+    let guild_id = context.guild_id().unwrap();
+
+    let Some(play_at) = parse_time(&time, unix_now()) else {
+        responses::error(
+            context,
+            "Invalid time specified. Use a delay like `1h30m` or a Discord timestamp like `<t:1700000000>`.",
+        )
+        .await?;
+        return Ok(());
+    };
+
+    context
+        .data()
+        .database
+        .add_scheduled_track(guild_id, context.channel_id(), context.author().id, &query, play_at as i64)
+        .await?;
+
+    responses::default(context, format!("Scheduled `{}` to be queued <t:{}:R>.", query, play_at)).await?;
+
+    Ok(())
+}
+
+/// List the tracks scheduled to be queued in this server.
+#[poise::command(slash_command, check = "checks::play_later")]
+async fn list(context: Context<'_>) -> Result<(), Error> {
+    let guild_id = context.guild_id().unwrap();
+    let scheduled_tracks = context.data().database.scheduled_tracks(guild_id).await?;
+
+    if scheduled_tracks.is_empty() {
+        responses::default(context, "There are no scheduled tracks.").await?;
+        return Ok(());
+    }
+
+    let description = scheduled_tracks
+        .iter()
+        .map(|scheduled_track| {
+            format!(
+                "**{}.** `{}` <t:{}:R> - <@{}>",
+                scheduled_track.id,
+                scheduled_track.query,
+                scheduled_track.play_at,
+                scheduled_track.requested_by()
+            )
+        })
+        .collect::<Vec<String>>()
+        .join("\n");
+
+    context
+        .send(|message| {
+            message.embed(|embed| {
+                embed
+                    .title("Scheduled Tracks")
+                    .description(description)
+                    .color(responses::Color::Default.to_color())
+            })
+        })
+        .await?;
+
+    Ok(())
+}
+
+/// Cancel a scheduled track, only DJs can cancel tracks scheduled by someone else.
+#[poise::command(slash_command, check = "checks::play_later")]
+async fn cancel(
+    context: Context<'_>,
+    #[description = "The number of the scheduled track, as shown by /playlater list"] id: i64,
+) -> Result<(), Error> {
+    let guild_id = context.guild_id().unwrap();
+    let database = &context.data().database;
+
+    let scheduled_tracks = database.scheduled_tracks(guild_id).await?;
+
+    let Some(scheduled_track) = scheduled_tracks.iter().find(|scheduled_track| scheduled_track.id == id) else {
+        responses::error(context, "There is no scheduled track with that number.").await?;
+        return Ok(());
+    };
+
+    if scheduled_track.requested_by() != context.author().id && !checks::is_dj(context).await? {
+        responses::ErrorMessage::UserNotDJ.say(context).await?;
+        return Ok(());
+    }
+
+    database.remove_scheduled_track(guild_id, id).await?;
+
+    responses::default(context, format!("Cancelled `{}`.", scheduled_track.query)).await?;
+
+    Ok(())
+}
+
+/// Queues scheduled tracks as they come due, for as long as the bot runs.
+///
+/// Tracks are only removed from the database once they've been queued, so a track that's due while nobody is in a
+/// voice channel waits until someone joins one, tracks that came due while the bot was offline play once it's back, and
+/// tracks that couldn't be queued because of an error, like Lavalink being down, are tried again on the next poll.
+/// Tracks that don't load anything are removed, as are tracks of guilds that have since been taken out of
+/// `PLAY_LATER_GUILDS`.
+pub async fn run_scheduler(context: serenity::Context, data: Data) {
+    let mut interval = tokio::time::interval(POLL_INTERVAL);
+
+    loop {
+        interval.tick().await;
+
+        let due = match data.database.due_scheduled_tracks(unix_now() as i64).await {
+            Ok(due) => due,
+            Err(why) => {
+                error!("Could not fetch scheduled tracks: {}", why);
+                continue;
+            }
+        };
+
+        for scheduled_track in due {
+            // Guilds can be removed from the configuration while they still have tracks scheduled
+            if data.play_later_guilds.contains(&scheduled_track.guild_id()) {
+                match play_scheduled(&context, &data, &scheduled_track).await {
+                    Ok(true) => info!("Queued scheduled track {} in {}", scheduled_track.id, scheduled_track.guild_id()),
+                    Ok(false) => continue,
+                    Err(why) => {
+                        error!("Could not queue scheduled track {}: {}", scheduled_track.id, why);
+                        continue;
+                    }
+                }
+            } else {
+                info!("Dropped scheduled track {} in {}, which can't use play later anymore", scheduled_track.id, scheduled_track.guild_id());
+            }
+
+            if let Err(why) = data
+                .database
+                .remove_scheduled_track(scheduled_track.guild_id(), scheduled_track.id)
+                .await
+            {
+                error!("Could not remove scheduled track {}: {}", scheduled_track.id, why);
+            }
+        }
+    }
+}
+
+/// Queues a scheduled track, joining the most populated voice channel first if the bot isn't connected.
+///
+/// Returns false if the bot isn't connected and nobody is in a voice channel to join. Tracks that don't load anything
+/// count as done, since they wouldn't load on the next try either.
+async fn play_scheduled(
+    context: &serenity::Context,
+    data: &Data,
+    scheduled_track: &ScheduledTrack,
+) -> Result<bool, Error> {
+    let guild_id = scheduled_track.guild_id();
+    let lava_client = data.lavalink.clone();
+
+    let Some(player_context) = join_most_populated(context, data, scheduled_track).await? else {
+        return Ok(false);
+    };
+
+    let loaded_tracks = play::load_tracks(&lava_client, guild_id, &scheduled_track.query).await?;
+
+    let mut tracks: VecDeque<TrackInQueue> = match loaded_tracks.data {
+        Some(TrackLoadData::Track(track)) => VecDeque::from([track.into()]),
+        Some(TrackLoadData::Search(search_results)) if !search_results.is_empty() => {
+            VecDeque::from([search_results[0].clone().into()])
+        }
+        Some(TrackLoadData::Playlist(playlist)) => playlist.tracks.iter().map(|track| track.into()).collect(),
+        _ => {
+            let description = format!("Couldn't find anything to play for `{}`.", scheduled_track.query);
+            notify(context, scheduled_track, description, responses::Color::Error).await;
+            return Ok(true);
+        }
+    };
+
+    set_requester(&mut tracks, scheduled_track.requested_by());
+
+    let is_playing = player_context.get_player().await?.track.is_some();
+    player_context.set_queue(QueueMessage::Append(tracks))?;
+
+    if !is_playing {
+        player_context.skip()?;
+    }
+
+    let description = format!(
+        "Queued scheduled track `{}` - <@{}>",
+        scheduled_track.query,
+        scheduled_track.requested_by()
+    );
+    notify(context, scheduled_track, description, responses::Color::Default).await;
+
+    Ok(true)
+}
+
+/// Sends a message to the channel a track was scheduled from. The track has been dealt with by then, so failing to send
+/// it is only logged, instead of having the track tried again.
+async fn notify(
+    context: &serenity::Context,
+    scheduled_track: &ScheduledTrack,
+    description: String,
+    color: responses::Color,
+) {
+    let result = scheduled_track
+        .channel_id()
+        .send_message(&context.http, |message| {
+            message.embed(|embed| embed.description(description).color(color.to_color()))
+        })
+        .await;
+
+    if let Err(why) = result {
+        warn!("Could not send message for scheduled track {}: {}", scheduled_track.id, why);
+    }
+}
+
+/// Returns the guild's player, joining the voice channel with the most listeners first if the bot isn't connected.
+async fn join_most_populated(
+    context: &serenity::Context,
+    data: &Data,
+    scheduled_track: &ScheduledTrack,
+) -> Result<Option<PlayerContext>, Error> {
+    let guild_id = scheduled_track.guild_id();
+
+    let manager = songbird::get(context).await.unwrap().clone();
+    let lava_client = data.lavalink.clone();
+
+    if manager.get(guild_id).is_some() {
+        if let Some(player_context) = lava_client.get_player_context(guild_id) {
+            return Ok(Some(player_context));
+        }
+    }
+
+    let Some(guild) = context.cache.guild(guild_id) else {
+        return Ok(None);
+    };
+
+    let Some(channel_id) = most_populated_channel(&guild, context.cache.current_user_id()) else {
+        return Ok(None);
+    };
+
+    let (_, handler) = manager.join_gateway(guild_id, channel_id).await;
+    let player_context = lava_client.create_player_context(guild_id, handler?).await?;
+    play::setup_player(data, guild_id, &player_context).await?;
+
+    // Announcements go where the track was scheduled from, like they would for the channel the bot was summoned from
+    data.summon_channels
+        .lock()
+        .unwrap()
+        .insert(guild_id, scheduled_track.channel_id());
+
+    Ok(Some(player_context))
+}
+
+/// Returns the voice channel with the most non-bot users in it, if anyone is in one.
+fn most_populated_channel(guild: &Guild, bot_id: UserId) -> Option<ChannelId> {
+    let mut listeners: HashMap<ChannelId, usize> = HashMap::new();
+
+    for voice_state in guild.voice_states.values() {
+        if voice_state.user_id == bot_id || voice_state.member.as_ref().is_some_and(|member| member.user.bot) {
+            continue;
+        }
+
+        if let Some(channel_id) = voice_state.channel_id {
+            *listeners.entry(channel_id).or_default() += 1;
+        }
+    }
+
+    listeners
+        .into_iter()
+        .max_by_key(|(_, count)| *count)
+        .map(|(channel_id, _)| channel_id)
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// Parses when a track should play as a unix timestamp, from either a Discord timestamp like `<t:1700000000:R>` or a
+/// delay like `1h30m` or `45s`. Returns `None` if the input is neither or the time has already passed.
+fn parse_time(input: &str, now: u64) -> Option<u64> {
+    let input = input.trim();
+
+    if let Some(timestamp) = input.strip_prefix("<t:").and_then(|rest| rest.strip_suffix('>')) {
+        let timestamp: u64 = timestamp.split(':').next()?.parse().ok()?;
+        return (timestamp > now).then_some(timestamp);
+    }
+
+    let delay = string_to_millis(input, true)? / 1000;
+
+    // A delay of nothing isn't a delay
+    if delay == 0 {
+        return None;
+    }
+
+    now.checked_add(delay)
+}
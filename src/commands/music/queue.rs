@@ -1,5 +1,5 @@
 use crate::{
-    commands::music::millis_to_string,
+    commands::music::{filter, looping::{self, LoopMode}, millis_at_speed, millis_to_string},
     responses::{self, Say},
     Context, Error,
 };
@@ -20,7 +20,10 @@ pub async fn queue(
         return Ok(());
     };
 
-    let currently_playing = player_context.get_player().await?.track;
+    let player = player_context.get_player().await?;
+    let currently_playing = player.track;
+
+    let speed = filter::playback_speed(player.filters.as_ref());
 
     if currently_playing.is_none() {
         responses::ErrorMessage::BotNotPlaying.say(context).await?;
@@ -58,7 +61,7 @@ pub async fn queue(
                 "**Now Playing**: [{}]({}) `{}`\n\n",
                 title,
                 uri,
-                millis_to_string(currently_playing.info.length),
+                millis_to_string(millis_at_speed(currently_playing.info.length, speed)),
             )
             .as_str(),
         ),
@@ -66,7 +69,7 @@ pub async fn queue(
             format!(
                 "**Now Playing**: {} `{}`\n\n",
                 title,
-                millis_to_string(currently_playing.info.length),
+                millis_to_string(millis_at_speed(currently_playing.info.length, speed)),
             )
             .as_str(),
         ),
@@ -99,7 +102,7 @@ pub async fn queue(
                         position,
                         title,
                         uri,
-                        millis_to_string(duration)
+                        millis_to_string(millis_at_speed(duration, speed))
                     )
                     .as_str(),
                 ),
@@ -108,7 +111,7 @@ pub async fn queue(
                         "**{}.** {} `{}`\n",
                         position,
                         title,
-                        millis_to_string(duration)
+                        millis_to_string(millis_at_speed(duration, speed))
                     )
                     .as_str(),
                 ),
@@ -125,14 +128,33 @@ pub async fn queue(
         }
     }
 
-    let queue_footer = format!(
+    let mut queue_footer = format!(
         "Page {}/{} | {} song(s) in queue | {} total duration",
         page,
         pages,
         queue_length,
-        millis_to_string(total_length),
+        millis_to_string(millis_at_speed(total_length, speed)),
     );
 
+    if player.paused {
+        queue_footer.push_str(" | Paused");
+    }
+
+    let active_filters = player
+        .filters
+        .as_ref()
+        .map(filter::active_filters)
+        .unwrap_or_default();
+
+    if !active_filters.is_empty() {
+        queue_footer.push_str(&format!(" | Filters: {}", active_filters.join(", ")));
+    }
+
+    let loop_mode = looping::loop_mode(context.data(), guild_id);
+    if loop_mode != LoopMode::Off {
+        queue_footer.push_str(&format!(" | Looping: {}", loop_mode.name()));
+    }
+
     context
         .send(|message| {
             message.embed(|embed| {
@@ -1,12 +1,13 @@
 use lavalink_rs::player_context::QueueMessage;
 
 use crate::{
+    checks,
     responses::{self, Say},
     Context, Error,
 };
 
 // Move a track from one position in the queue to another
-#[poise::command(slash_command)]
+#[poise::command(slash_command, check = "checks::dj")]
 pub async fn remove(
     context: Context<'_>,
     #[description = "The position of the track in the queue that te be removed from the queue."]
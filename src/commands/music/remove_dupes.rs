@@ -0,0 +1,113 @@
+use std::collections::{HashSet, VecDeque};
+
+use lavalink_rs::player_context::{QueueMessage, TrackInQueue};
+
+use crate::{
+    checks,
+    responses::{self, Say},
+    Context, Error,
+};
+
+/// The most removed tracks listed in the response, any more are only counted.
+const LISTED_TRACKS: usize = 10;
+
+/// Remove duplicate tracks from the queue, keeping the first occurrence of each.
+#[poise::command(slash_command, rename = "removedupes", check = "checks::dj")]
+pub async fn remove_dupes(
+    context: Context<'_>,
+    #[description = "Also treat tracks with the same title and author as duplicates."]
+    #[rename = "by_title"]
+    compare_titles: Option<bool>,
+) -> Result<(), Error> {
+    let guild = context.guild().unwrap();
+    let guild_id = guild.id;
+
+    let lava_client = context.data().lavalink.clone();
+
+    let Some(player_context) = lava_client.get_player_context(guild_id) else {
+        responses::ErrorMessage::BotNotInVC.say(context).await?;
+        return Ok(());
+    };
+
+    if player_context.get_player().await?.track.is_none() {
+        responses::ErrorMessage::BotNotPlaying.say(context).await?;
+        return Ok(());
+    }
+
+    let queue = player_context.get_queue().await?;
+    let (queue, removed) = split_duplicates(queue, compare_titles.unwrap_or(false));
+
+    if removed.is_empty() {
+        responses::default(context, "There are no duplicate tracks in the queue.").await?;
+        return Ok(());
+    }
+
+    player_context.set_queue(QueueMessage::Replace(queue))?;
+
+    let mut message = format!("Removed **{}** duplicate track(s) from the queue.\n", removed.len());
+
+    for wrapped_track in removed.iter().take(LISTED_TRACKS) {
+        let track = &wrapped_track.track;
+
+        match &track.info.uri {
+            Some(uri) => message.push_str(&format!("\n[{} - {}]({})", track.info.author, track.info.title, uri)),
+            None => message.push_str(&format!("\n{} - {}", track.info.author, track.info.title)),
+        }
+    }
+
+    if removed.len() > LISTED_TRACKS {
+        message.push_str(&format!("\n...and {} more", removed.len() - LISTED_TRACKS));
+    }
+
+    responses::default(context, message).await?;
+
+    Ok(())
+}
+
+/// Splits the queue into the tracks that are kept and the duplicates that were removed, in queue order.
+fn split_duplicates(
+    queue: VecDeque<TrackInQueue>,
+    compare_titles: bool,
+) -> (VecDeque<TrackInQueue>, Vec<TrackInQueue>) {
+    let mut identifiers = HashSet::new();
+    let mut uris = HashSet::new();
+    let mut titles = HashSet::new();
+
+    let mut kept = VecDeque::new();
+    let mut removed = Vec::new();
+
+    for wrapped_track in queue {
+        let info = &wrapped_track.track.info;
+
+        // Every check has to run so the track is remembered by all of its keys, even if an earlier one matched
+        let mut duplicate = !identifiers.insert(info.identifier.clone());
+
+        if let Some(uri) = &info.uri {
+            duplicate |= !uris.insert(uri.clone());
+        }
+
+        if compare_titles {
+            duplicate |= !titles.insert(normalize(&info.title, &info.author));
+        }
+
+        if duplicate {
+            removed.push(wrapped_track);
+        } else {
+            kept.push_back(wrapped_track);
+        }
+    }
+
+    (kept, removed)
+}
+
+/// Reduces a title and author to lowercase letters and digits, so differences in punctuation and spacing are ignored.
+fn normalize(title: &str, author: &str) -> (String, String) {
+    let simplify = |text: &str| -> String {
+        text.chars()
+            .filter(|character| character.is_alphanumeric())
+            .flat_map(char::to_lowercase)
+            .collect()
+    };
+
+    (simplify(title), simplify(author))
+}
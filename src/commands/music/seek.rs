@@ -0,0 +1,207 @@
+use std::time::Duration;
+
+use crate::{
+    checks,
+    commands::music::{filter, millis_at_speed, millis_in_track, millis_to_string, string_to_millis},
+    responses::{self, Say},
+    Context, Error,
+};
+
+/// How far /rewind and /forward move when no amount is given.
+const DEFAULT_STEP: &str = "10s";
+
+/// Where in the current track to seek to, as entered by the user.
+enum SeekTarget {
+    Position(u64),
+    Forward(u64),
+    Backward(u64),
+    Percentage(f64),
+}
+
+impl SeekTarget {
+    /// Parses a timestamp like `1:23`, a relative duration like `+30s` or `-10s`, or a percentage like `50%`.
+    fn parse(input: &str) -> Option<Self> {
+        let input = input.trim();
+
+        if let Some(percentage) = input.strip_suffix('%') {
+            let percentage: f64 = percentage.trim().parse().ok()?;
+            return (0.0..=100.0).contains(&percentage).then_some(Self::Percentage(percentage));
+        }
+
+        if let Some(duration) = input.strip_prefix('+') {
+            return string_to_millis(duration, false).map(Self::Forward);
+        }
+
+        if let Some(duration) = input.strip_prefix('-') {
+            return string_to_millis(duration, false).map(Self::Backward);
+        }
+
+        string_to_millis(input, false).map(Self::Position)
+    }
+
+    /// Returns the position to seek to in milliseconds, which can be past the end of the track.
+    fn resolve(&self, position: u64, length: u64) -> u64 {
+        use SeekTarget::*;
+        match self {
+            Position(millis) => *millis,
+            Forward(millis) => position.saturating_add(*millis),
+            Backward(millis) => position.saturating_sub(*millis),
+            Percentage(percentage) => (length as f64 * percentage / 100.0) as u64,
+        }
+    }
+}
+
+/// Seek to a position in the currently playing track
+#[poise::command(slash_command, check = "checks::dj")]
+pub async fn seek(
+    context: Context<'_>,
+    #[description = "A timestamp like 1:23, a duration like +30s or -10s, or a percentage like 50%"]
+    position: String,
+) -> Result<(), Error> {
+    let Some(target) = SeekTarget::parse(&position) else {
+        responses::ErrorMessage::InvalidTimestamp.say(context).await?;
+        return Ok(());
+    };
+
+    seek_to(context, target).await
+}
+
+/// Rewind the currently playing track
+#[poise::command(slash_command, check = "checks::dj")]
+pub async fn rewind(
+    context: Context<'_>,
+    #[description = "How far to rewind, like 30s or 1:00 (Defaults to 10 seconds)"] amount: Option<String>,
+) -> Result<(), Error> {
+    let Some(millis) = string_to_millis(amount.as_deref().unwrap_or(DEFAULT_STEP), false) else {
+        responses::ErrorMessage::InvalidDuration.say(context).await?;
+        return Ok(());
+    };
+
+    seek_to(context, SeekTarget::Backward(millis)).await
+}
+
+/// Fast forward the currently playing track
+#[poise::command(slash_command, check = "checks::dj")]
+pub async fn forward(
+    context: Context<'_>,
+    #[description = "How far to fast forward, like 30s or 1:00 (Defaults to 10 seconds)"] amount: Option<String>,
+) -> Result<(), Error> {
+    let Some(millis) = string_to_millis(amount.as_deref().unwrap_or(DEFAULT_STEP), false) else {
+        responses::ErrorMessage::InvalidDuration.say(context).await?;
+        return Ok(());
+    };
+
+    seek_to(context, SeekTarget::Forward(millis)).await
+}
+
+async fn seek_to(context: Context<'_>, target: SeekTarget) -> Result<(), Error> {
+    let guild_id = context.guild_id().unwrap();
+
+    let lava_client = context.data().lavalink.clone();
+
+    let Some(player_context) = lava_client.get_player_context(guild_id) else {
+        responses::ErrorMessage::BotNotInVC.say(context).await?;
+        return Ok(());
+    };
+
+    let player = player_context.get_player().await?;
+
+    let Some(track) = player.track else {
+        responses::ErrorMessage::BotNotPlaying.say(context).await?;
+        return Ok(());
+    };
+
+    if !track.info.is_seekable {
+        responses::ErrorMessage::TrackNotSeekable.say(context).await?;
+        return Ok(());
+    }
+
+    let speed = filter::playback_speed(player.filters.as_ref());
+    let position = millis_at_speed(player.state.position, speed);
+    let length = millis_at_speed(track.info.length, speed);
+
+    let new_position = target.resolve(position, length);
+
+    if new_position > length {
+        responses::ErrorMessage::SeekPastEnd.say(context).await?;
+        return Ok(());
+    }
+
+    let track_position = millis_in_track(new_position, speed).min(track.info.length);
+
+    player_context
+        .set_position(Duration::from_millis(track_position))
+        .await?;
+
+    responses::default(
+        context,
+        format!(
+            "Seeked to `{}` / `{}`.",
+            millis_to_string(new_position),
+            millis_to_string(length)
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_durations() {
+        assert_eq!(string_to_millis("1:23", false), Some(83_000));
+        assert_eq!(string_to_millis("01:02:03", false), Some(3_723_000));
+        assert_eq!(string_to_millis("90s", false), Some(90_000));
+        assert_eq!(string_to_millis("1m30s", false), Some(90_000));
+        assert_eq!(string_to_millis("1H2M", false), Some(3_720_000));
+        assert_eq!(string_to_millis(" 45 ", false), Some(45_000));
+    }
+
+    #[test]
+    fn rejects_invalid_durations() {
+        assert_eq!(string_to_millis("", false), None);
+        assert_eq!(string_to_millis("  ", false), None);
+        assert_eq!(string_to_millis("1:2:3:4", false), None);
+        assert_eq!(string_to_millis("1:", false), None);
+        assert_eq!(string_to_millis("m", false), None);
+        assert_eq!(string_to_millis("10x", false), None);
+        assert_eq!(string_to_millis("-5", false), None);
+    }
+
+    #[test]
+    fn parses_durations_with_units_only() {
+        assert_eq!(string_to_millis("1h30m", true), Some(5_400_000));
+        assert_eq!(string_to_millis("45s", true), Some(45_000));
+        assert_eq!(string_to_millis("1:00", true), None);
+        assert_eq!(string_to_millis("5", true), None);
+        assert_eq!(string_to_millis("1m30", true), None);
+    }
+
+    #[test]
+    fn parses_seek_targets() {
+        assert!(matches!(SeekTarget::parse("1:23"), Some(SeekTarget::Position(83_000))));
+        assert!(matches!(SeekTarget::parse("+30s"), Some(SeekTarget::Forward(30_000))));
+        assert!(matches!(SeekTarget::parse("-10s"), Some(SeekTarget::Backward(10_000))));
+        assert!(matches!(SeekTarget::parse("50%"), Some(SeekTarget::Percentage(percentage)) if percentage == 50.0));
+    }
+
+    #[test]
+    fn rejects_invalid_seek_targets() {
+        assert!(SeekTarget::parse("+").is_none());
+        assert!(SeekTarget::parse("-").is_none());
+        assert!(SeekTarget::parse("").is_none());
+        assert!(SeekTarget::parse("150%").is_none());
+        assert!(SeekTarget::parse("%").is_none());
+    }
+
+    #[test]
+    fn resolves_seek_targets() {
+        assert_eq!(SeekTarget::Position(5_000).resolve(60_000, 120_000), 5_000);
+        assert_eq!(SeekTarget::Forward(30_000).resolve(60_000, 120_000), 90_000);
+        assert_eq!(SeekTarget::Backward(90_000).resolve(60_000, 120_000), 0);
+        assert_eq!(SeekTarget::Percentage(25.0).resolve(60_000, 120_000), 30_000);
+    }
+}
@@ -0,0 +1,99 @@
+use std::collections::{HashMap, VecDeque};
+
+use lavalink_rs::player_context::TrackInQueue;
+use poise::serenity_prelude::UserId;
+use rand::{seq::SliceRandom, Rng};
+
+use crate::{
+    checks,
+    commands::music::{rearrange_queue, requester},
+    responses::{self, Say},
+    Context, Error,
+};
+
+#[derive(poise::ChoiceParameter, Clone, Copy, PartialEq)]
+pub enum ShuffleMode {
+    #[name = "Random"]
+    Random,
+    #[name = "Fair (Spread out each person's tracks)"]
+    Fair,
+}
+
+/// Shuffle the queue
+#[poise::command(slash_command, check = "checks::dj")]
+pub async fn shuffle(
+    context: Context<'_>,
+    #[description = "How to shuffle the queue (Defaults to random)"] mode: Option<ShuffleMode>,
+) -> Result<(), Error> {
+    let guild_id = context.guild_id().unwrap();
+    let data = context.data();
+
+    let lava_client = data.lavalink.clone();
+
+    let Some(player_context) = lava_client.get_player_context(guild_id) else {
+        responses::ErrorMessage::BotNotInVC.say(context).await?;
+        return Ok(());
+    };
+
+    let mode = mode.unwrap_or(ShuffleMode::Random);
+
+    let shuffled = rearrange_queue(&player_context, |queue| {
+        if queue.len() < 2 {
+            return None;
+        }
+
+        let tracks = std::mem::take(queue);
+
+        *queue = match mode {
+            ShuffleMode::Random => random_shuffle(tracks),
+            ShuffleMode::Fair => fair_shuffle(tracks),
+        };
+
+        Some(())
+    })
+    .await?;
+
+    match (shuffled, mode) {
+        (None, _) => responses::error(context, "There aren't enough tracks in the queue to shuffle.").await?,
+        (Some(_), ShuffleMode::Random) => responses::default(context, "Shuffled the queue.").await?,
+        (Some(_), ShuffleMode::Fair) => {
+            responses::default(context, "Shuffled the queue, spreading out each person's tracks.").await?
+        }
+    }
+
+    Ok(())
+}
+
+pub fn random_shuffle(mut tracks: VecDeque<TrackInQueue>) -> VecDeque<TrackInQueue> {
+    tracks.make_contiguous().shuffle(&mut rand::thread_rng());
+    tracks
+}
+
+/// Shuffles the tracks so that each requester's tracks are spread evenly through the queue.
+///
+/// Every requester's tracks are shuffled among themselves, then the `i`th of `n` tracks is placed at `(i + offset) / n`
+/// of the way through the queue, with a random offset per requester so nobody's tracks always come first.
+pub fn fair_shuffle(tracks: VecDeque<TrackInQueue>) -> VecDeque<TrackInQueue> {
+    let mut rng = rand::thread_rng();
+
+    let mut requesters: HashMap<Option<UserId>, Vec<TrackInQueue>> = HashMap::new();
+    for track in tracks {
+        requesters.entry(requester(&track.track)).or_default().push(track);
+    }
+
+    let mut placed: Vec<(f64, TrackInQueue)> = Vec::new();
+
+    for mut requester_tracks in requesters.into_values() {
+        requester_tracks.shuffle(&mut rng);
+
+        let count = requester_tracks.len() as f64;
+        let offset: f64 = rng.gen();
+
+        for (index, track) in requester_tracks.into_iter().enumerate() {
+            placed.push(((index as f64 + offset) / count, track));
+        }
+    }
+
+    placed.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+    placed.into_iter().map(|(_, track)| track).collect()
+}
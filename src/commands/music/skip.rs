@@ -0,0 +1,110 @@
+use std::collections::HashSet;
+
+use poise::serenity_prelude::UserId;
+
+use crate::{
+    checks,
+    commands::music::{force_skip, voice_listeners},
+    responses::{self, Say},
+    Context, Error,
+};
+
+/// The votes cast to skip the track that is currently playing in a guild.
+pub struct SkipVotes {
+    /// The encoded track the votes were cast against, so that votes reset when the track changes.
+    track: String,
+    voters: HashSet<UserId>,
+}
+
+/// Vote to skip the currently playing track
+#[poise::command(slash_command)]
+pub async fn skip(
+    context: Context<'_>,
+    #[description = "The position in the queue to skip to, for DJs when DJ skip is forceskip is enabled"]
+    position: Option<usize>,
+) -> Result<(), Error> {
+    let guild = context.guild().unwrap();
+    let guild_id = guild.id;
+
+    let settings = context.data().settings.get(guild_id).await?;
+
+    if settings.dj_skip_is_forceskip && checks::is_dj(context).await? {
+        return force_skip::skip_without_voting(context, position).await;
+    }
+
+    if position.is_some() {
+        responses::error(context, "Only DJs can skip to a position in the queue.").await?;
+        return Ok(());
+    }
+
+    let lava_client = context.data().lavalink.clone();
+
+    let Some(player_context) = lava_client.get_player_context(guild_id) else {
+        responses::ErrorMessage::BotNotInVC.say(context).await?;
+        return Ok(());
+    };
+
+    let Some(track) = player_context.get_player().await?.track else {
+        responses::ErrorMessage::BotNotPlaying.say(context).await?;
+        return Ok(());
+    };
+
+    let bot_id = context.serenity_context().cache.current_user_id();
+    let Some((_, listeners)) = voice_listeners(&guild, bot_id) else {
+        responses::ErrorMessage::BotNotInVC.say(context).await?;
+        return Ok(());
+    };
+
+    let author_id = context.author().id;
+    if !listeners.contains(&author_id) {
+        responses::ErrorMessage::UserNotListening.say(context).await?;
+        return Ok(());
+    }
+
+    let threshold = settings.skip_threshold as usize;
+    let required = ((listeners.len() * threshold + 99) / 100).max(1);
+
+    // The lock can't be held across an await, so work out the outcome of the vote first
+    let votes = {
+        let mut skip_votes = context.data().skip_votes.lock().unwrap();
+        let guild_votes = skip_votes.entry(guild_id).or_insert_with(|| SkipVotes {
+            track: track.encoded.clone(),
+            voters: HashSet::new(),
+        });
+
+        if guild_votes.track != track.encoded {
+            guild_votes.track = track.encoded.clone();
+            guild_votes.voters.clear();
+        }
+
+        // Votes from people that have since left the channel no longer count
+        guild_votes.voters.retain(|voter| listeners.contains(voter));
+
+        if guild_votes.voters.insert(author_id) {
+            let votes = guild_votes.voters.len();
+
+            if votes >= required {
+                skip_votes.remove(&guild_id);
+            }
+
+            Some(votes)
+        } else {
+            None
+        }
+    };
+
+    let Some(votes) = votes else {
+        responses::ErrorMessage::AlreadyVoted.say(context).await?;
+        return Ok(());
+    };
+
+    if votes < required {
+        responses::DefaultMessage::SkipVote(votes, required).say(context).await?;
+        return Ok(());
+    }
+
+    responses::DefaultMessage::VoteSkipped(votes, required).say(context).await?;
+    player_context.skip()?;
+
+    Ok(())
+}
@@ -0,0 +1,238 @@
+use http::Method;
+use lavalink_rs::prelude::LavalinkClient;
+use poise::{serenity_prelude::GuildId, ChoiceParameter};
+
+use crate::{
+    commands::music::{millis_to_string, now_playing},
+    responses, Data, Error,
+};
+
+/// The kinds of segments the Lavalink SponsorBlock plugin can skip.
+///
+/// A guild's enabled categories are stored as a bitmask, with each category's bit given by its position here, so new
+/// categories have to be added at the end.
+#[derive(poise::ChoiceParameter, Clone, Copy, PartialEq, Eq)]
+pub enum SponsorBlockCategory {
+    #[name = "Sponsor"]
+    Sponsor,
+    #[name = "Unpaid/Self Promotion"]
+    SelfPromo,
+    #[name = "Interaction Reminder"]
+    Interaction,
+    #[name = "Intro"]
+    Intro,
+    #[name = "Outro"]
+    Outro,
+    #[name = "Preview/Recap"]
+    Preview,
+    #[name = "Non-Music Section"]
+    MusicOfftopic,
+    #[name = "Filler Tangent"]
+    Filler,
+}
+
+impl SponsorBlockCategory {
+    const ALL: [Self; 8] = [
+        Self::Sponsor,
+        Self::SelfPromo,
+        Self::Interaction,
+        Self::Intro,
+        Self::Outro,
+        Self::Preview,
+        Self::MusicOfftopic,
+        Self::Filler,
+    ];
+
+    /// The category's name in the SponsorBlock api.
+    pub fn id(&self) -> &'static str {
+        use SponsorBlockCategory::*;
+        match self {
+            Sponsor => "sponsor",
+            SelfPromo => "selfpromo",
+            Interaction => "interaction",
+            Intro => "intro",
+            Outro => "outro",
+            Preview => "preview",
+            MusicOfftopic => "music_offtopic",
+            Filler => "filler",
+        }
+    }
+
+    pub fn bit(&self) -> i64 {
+        1 << *self as i64
+    }
+
+    /// Returns the categories enabled in a bitmask.
+    pub fn from_bitmask(bitmask: i64) -> Vec<Self> {
+        Self::ALL
+            .into_iter()
+            .filter(|category| bitmask & category.bit() != 0)
+            .collect()
+    }
+
+    fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|category| category.id() == id)
+    }
+}
+
+/// Tells the SponsorBlock plugin which categories to skip for a guild's player. An empty list turns skipping off.
+pub async fn set_categories(
+    lava_client: &LavalinkClient,
+    guild_id: GuildId,
+    categories: &[SponsorBlockCategory],
+) -> Result<(), Error> {
+    let node = lava_client.get_node_for_guild(guild_id).await;
+
+    let path = format!(
+        "/sessions/{}/players/{}/sponsorblock/categories",
+        node.session_id.load(),
+        guild_id.0
+    );
+    let uri = node.http.path_to_uri(&path, true)?;
+
+    let categories: Vec<&str> = categories.iter().map(|category| category.id()).collect();
+    node.http.raw_request(Method::PUT, uri, &categories).await?;
+
+    Ok(())
+}
+
+/// Called whenever a player is created, since the plugin forgets the categories along with the previous player.
+pub async fn apply(data: &Data, guild_id: GuildId) -> Result<(), Error> {
+    let settings = data.settings.get(guild_id).await?;
+
+    // Guilds that never turned SponsorBlock on shouldn't need the plugin
+    if settings.sponsorblock_categories == 0 {
+        return Ok(());
+    }
+
+    let categories = SponsorBlockCategory::from_bitmask(settings.sponsorblock_categories);
+    set_categories(&data.lavalink, guild_id, &categories).await
+}
+
+/// Called for the plugin's `SegmentSkipped` event, announces the skip if the guild has it turned on.
+pub async fn announce_skip(data: &Data, event: &serde_json::Value) -> Result<(), Error> {
+    let Some(guild_id) = event["guildId"].as_str().and_then(|guild_id| guild_id.parse().ok()) else {
+        return Ok(());
+    };
+    let guild_id = GuildId(guild_id);
+
+    let settings = data.settings.get(guild_id).await?;
+
+    if !settings.announce_sponsorblock {
+        return Ok(());
+    }
+
+    let Some(channel_id) = now_playing::announcement_channel(data, guild_id, &settings) else {
+        return Ok(());
+    };
+
+    let segment = &event["segment"];
+
+    let category = segment["category"]
+        .as_str()
+        .and_then(SponsorBlockCategory::from_id)
+        .map_or("Unknown", |category| category.name());
+    let start = segment["start"].as_u64().unwrap_or_default();
+    let end = segment["end"].as_u64().unwrap_or_default();
+
+    channel_id
+        .send_message(&data.http, |message| {
+            message.embed(|embed| {
+                embed
+                    .description(format!(
+                        "Skipped **{}** segment `{}` - `{}`",
+                        category,
+                        millis_to_string(start),
+                        millis_to_string(end)
+                    ))
+                    .color(responses::Color::Default.to_color())
+            })
+        })
+        .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use lavalink_rs::{model::events::Events, prelude::NodeBuilder};
+    use poise::serenity_prelude::UserId;
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    use super::*;
+
+    /// Answers a single request on a local port like Lavalink would, returning the request line and body it received.
+    async fn mock_lavalink(listener: TcpListener) -> (String, String) {
+        let (mut stream, _) = listener.accept().await.unwrap();
+
+        let mut request = Vec::new();
+        let mut buffer = [0; 1024];
+
+        let (head, body) = loop {
+            let read = stream.read(&mut buffer).await.unwrap();
+            assert!(read > 0, "the request ended early");
+            request.extend_from_slice(&buffer[..read]);
+
+            let text = String::from_utf8_lossy(&request).to_string();
+            let Some((head, body)) = text.split_once("\r\n\r\n") else {
+                continue;
+            };
+
+            let content_length = head
+                .lines()
+                .find_map(|line| line.to_ascii_lowercase().strip_prefix("content-length:").map(str::to_owned))
+                .and_then(|length| length.trim().parse().ok())
+                .unwrap_or(0);
+
+            if body.len() >= content_length {
+                break (head.to_owned(), body.to_owned());
+            }
+        };
+
+        stream
+            .write_all(b"HTTP/1.1 204 No Content\r\ncontent-length: 0\r\n\r\n")
+            .await
+            .unwrap();
+
+        (head.lines().next().unwrap().to_owned(), body)
+    }
+
+    #[tokio::test]
+    async fn set_categories_puts_category_ids() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let lavalink = tokio::spawn(mock_lavalink(listener));
+
+        let node = NodeBuilder {
+            hostname: address.to_string(),
+            is_ssl: false,
+            events: Events::default(),
+            password: "youshallnotpass".to_owned(),
+            user_id: UserId(1).into(),
+            session_id: Some("session".to_owned()),
+        };
+        let lava_client = LavalinkClient::new(Events::default(), vec![node]);
+
+        let categories = [SponsorBlockCategory::Sponsor, SponsorBlockCategory::MusicOfftopic];
+        let result = set_categories(&lava_client, GuildId(1234), &categories).await;
+
+        let (request_line, body) = lavalink.await.unwrap();
+
+        assert!(request_line.starts_with("PUT "));
+        assert!(request_line.contains("/sessions/session/players/1234/sponsorblock/categories"));
+        assert_eq!(serde_json::from_str::<Vec<String>>(&body).unwrap(), ["sponsor", "music_offtopic"]);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn bitmask_round_trips() {
+        let categories = [SponsorBlockCategory::SelfPromo, SponsorBlockCategory::Filler];
+        let bitmask = categories.iter().fold(0, |bitmask, category| bitmask | category.bit());
+
+        assert!(SponsorBlockCategory::from_bitmask(bitmask) == categories);
+        assert!(SponsorBlockCategory::from_bitmask(0).is_empty());
+    }
+}
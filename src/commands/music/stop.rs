@@ -0,0 +1,29 @@
+use crate::{
+    checks,
+    responses::{self, Say},
+    Context, Error,
+};
+
+/// Stop the currently playing track, keeping the rest of the queue for /resume
+#[poise::command(slash_command, check = "checks::dj")]
+pub async fn stop(context: Context<'_>) -> Result<(), Error> {
+    let guild_id = context.guild_id().unwrap();
+
+    let lava_client = context.data().lavalink.clone();
+
+    let Some(player_context) = lava_client.get_player_context(guild_id) else {
+        responses::ErrorMessage::BotNotInVC.say(context).await?;
+        return Ok(());
+    };
+
+    if player_context.get_player().await?.track.is_none() {
+        responses::ErrorMessage::BotNotPlaying.say(context).await?;
+        return Ok(());
+    }
+
+    // Stopping doesn't start the next track, unlike skipping
+    player_context.stop_now().await?;
+    responses::DefaultMessage::Stopped.say(context).await?;
+
+    Ok(())
+}
@@ -0,0 +1,42 @@
+use crate::{
+    checks,
+    responses::{self, Say},
+    Context, Error,
+};
+
+/// Show or change the volume
+#[poise::command(slash_command, check = "checks::dj")]
+pub async fn volume(
+    context: Context<'_>,
+    #[description = "The new volume as a percentage, leave empty to show the current volume"]
+    #[min = 0]
+    #[max = 1000]
+    percent: Option<u16>,
+) -> Result<(), Error> {
+    let guild_id = context.guild_id().unwrap();
+
+    let lava_client = context.data().lavalink.clone();
+
+    let Some(player_context) = lava_client.get_player_context(guild_id) else {
+        responses::ErrorMessage::BotNotInVC.say(context).await?;
+        return Ok(());
+    };
+
+    let Some(percent) = percent else {
+        let player = player_context.get_player().await?;
+        responses::default(context, format!("The volume is **{}%**.", player.volume)).await?;
+        return Ok(());
+    };
+
+    let max_volume = context.data().settings.get(guild_id).await?.max_volume;
+
+    if percent as i64 > max_volume {
+        responses::error(context, format!("The volume can't be set above **{}%** in this server.", max_volume)).await?;
+        return Ok(());
+    }
+
+    player_context.set_volume(percent).await?;
+    responses::default(context, format!("Set the volume to **{}%**.", percent)).await?;
+
+    Ok(())
+}
@@ -1,23 +1,114 @@
-use core::fmt;
-use std::vec;
-
-use crate::{Context, Error, database::{INTEGER, BOOL, ColumnType, ValidValue}};
-use poise::serenity_prelude as serenity;
-
-const GUILD_ID: &str = "guild_id";
-const DJ_ID: &str = "dj_id";
-const DJ_ONLY: &str = "dj_only";
-const ANNOUNCE_SONGS: &str = "announce_songs";
-const EVERYONE_DJ: &str = "everyone_dj";
-const TABLE_NAME: &str = "guild_settings";
-const TABLE_COLUMNS: [&str; 5] = [GUILD_ID, DJ_ID, DJ_ONLY, ANNOUNCE_SONGS, EVERYONE_DJ];
-const TABLE_TYPES: [ColumnType; 5] = [INTEGER, INTEGER, BOOL, BOOL, BOOL];
-const DEFAULT_VALUES: [&str; 5] = ["0", "0", "0", "0", "0"];
-
-async fn update_value<T: ValidValue + fmt::Display>(context: Context<'_>, key: &str, value: &T) {
-    let database = &context.data().database;
+use crate::{Context, Data, Error, commands::music::{eq, sponsorblock::{self, SponsorBlockCategory}}, database::{GuildSettings, Setting}, responses};
+use poise::{serenity_prelude::{self as serenity, ChannelId, GuildId, RoleId}, ChoiceParameter};
+
+const DJ_ROLE: &str = "DJ Role";
+const DJ_ONLY: &str = "DJ Only Mode";
+const EVERYONE_DJ: &str = "Everyone DJ";
+const ANNOUNCE_SONGS: &str = "Announce Songs";
+const ANNOUNCE_CHANNEL: &str = "Announcement Channel";
+const SKIP_THRESHOLD: &str = "Skip Threshold";
+const AUTO_ABSENT_CLEANUP: &str = "Automatic Absent Cleanup";
+const FOLLOW_FIRST_LISTENER: &str = "Follow First Listener";
+const DJ_SKIP_IS_FORCESKIP: &str = "DJ Skip is Forceskip";
+const DEFAULT_VOLUME: &str = "Default Volume";
+const MAX_VOLUME: &str = "Maximum Volume";
+const DEFAULT_EQ_PROFILE: &str = "Default EQ Profile";
+const SHUFFLE_PLAYLISTS: &str = "Shuffle Playlists";
+const FAIR_QUEUE: &str = "Fair Queue";
+const SPONSORBLOCK: &str = "SponsorBlock";
+const ANNOUNCE_SPONSORBLOCK: &str = "Announce SponsorBlock Skips";
+
+/// The name and formatted value of every setting, in the order they're shown.
+async fn fields(data: &Data, settings: &GuildSettings) -> Result<Vec<(&'static str, String)>, Error> {
+    Ok(vec![
+        (DJ_ROLE, format_role(settings.dj_role())),
+        (DJ_ONLY, format_bool(settings.dj_only)),
+        (EVERYONE_DJ, format_bool(settings.everyone_dj)),
+        (DJ_SKIP_IS_FORCESKIP, format_bool(settings.dj_skip_is_forceskip)),
+        (ANNOUNCE_SONGS, format_bool(settings.announce_songs)),
+        (ANNOUNCE_CHANNEL, format_channel(settings.announce_channel())),
+        (SKIP_THRESHOLD, format!("{}% of listeners", settings.skip_threshold)),
+        (DEFAULT_VOLUME, format!("{}%", settings.default_volume)),
+        (MAX_VOLUME, format!("{}%", settings.max_volume)),
+        (DEFAULT_EQ_PROFILE, format_eq_profile(data, settings).await?),
+        (SHUFFLE_PLAYLISTS, format_bool(settings.shuffle_playlists)),
+        (FAIR_QUEUE, format_bool(settings.fair_queue)),
+        (AUTO_ABSENT_CLEANUP, format_bool(settings.auto_absent_cleanup)),
+        (FOLLOW_FIRST_LISTENER, format_bool(settings.follow_first_listener)),
+        (SPONSORBLOCK, format_categories(settings.sponsorblock_categories)),
+        (ANNOUNCE_SPONSORBLOCK, format_bool(settings.announce_sponsorblock)),
+    ])
+}
+
+fn format_bool(value: bool) -> String {
+    match value {
+        true => "Enabled".to_owned(),
+        false => "Disabled".to_owned(),
+    }
+}
+
+fn format_role(role: Option<RoleId>) -> String {
+    match role {
+        Some(role) => format!("<@&{}>", role),
+        None => "None".to_owned(),
+    }
+}
+
+fn format_channel(channel: Option<ChannelId>) -> String {
+    match channel {
+        Some(channel) => format!("<#{}>", channel),
+        None => "Channel the bot was summoned from".to_owned(),
+    }
+}
+
+async fn format_eq_profile(data: &Data, settings: &GuildSettings) -> Result<String, Error> {
+    if settings.default_eq_profile_id == 0 {
+        return Ok("None".to_owned());
+    }
+
+    let guild_id = GuildId(settings.guild_id as u64);
+    let profiles = data.database.eq_profiles(guild_id).await?;
+
+    Ok(profiles
+        .into_iter()
+        .find(|profile| profile.id == settings.default_eq_profile_id)
+        .map_or_else(|| "None".to_owned(), |profile| profile.name))
+}
+
+fn format_categories(bitmask: i64) -> String {
+    let categories = SponsorBlockCategory::from_bitmask(bitmask);
+
+    if categories.is_empty() {
+        return "Disabled".to_owned();
+    }
+
+    categories
+        .iter()
+        .map(|category| category.name())
+        .collect::<Vec<&str>>()
+        .join(", ")
+}
+
+fn format_field(name: &str, value: &str) -> String {
+    format!("**{}**: {}", name, value)
+}
+
+/// Stores the new value of a setting, then confirms it formatted the same way as in `/settings show`.
+async fn update_value(context: Context<'_>, name: &str, setting: Setting) -> Result<(), Error> {
     let guild_id = context.guild_id().unwrap();
-    database.update_value(TABLE_NAME, TABLE_COLUMNS.to_vec(), TABLE_TYPES.to_vec(), DEFAULT_VALUES.to_vec(), key, value, GUILD_ID, &guild_id).await;
+    let data = context.data();
+
+    data.settings.update(guild_id, setting).await?;
+
+    let settings = data.settings.get(guild_id).await?;
+    let (_, value) = fields(data, &settings)
+        .await?
+        .into_iter()
+        .find(|(field, _)| *field == name)
+        .unwrap();
+
+    responses::setting(context, format_field(name, &value)).await?;
+    Ok(())
 }
 
 // Discord doesn't permit invoking the root command of a slash command if it has subcommands, so the root command goes unused.
@@ -28,7 +119,19 @@ async fn update_value<T: ValidValue + fmt::Display>(context: Context<'_>, key: &
         "dj_role",
         "dj_only",
         "everyone_dj",
+        "dj_skip_is_forceskip",
         "announce_songs",
+        "announce_channel",
+        "skip_threshold",
+        "default_volume",
+        "max_volume",
+        "default_eq_profile",
+        "shuffle_playlists",
+        "fair_queue",
+        "auto_absent_cleanup",
+        "follow_first_listener",
+        "sponsorblock",
+        "announce_sponsorblock",
     ),
     required_permissions = "MANAGE_GUILD"
 )]
@@ -39,7 +142,28 @@ pub async fn settings(_: Context<'_>) -> Result<(), Error> {
 /// Show the bot's current settings.
 #[poise::command(slash_command)]
 async fn show(context: Context<'_>) -> Result<(), Error> {
-    context.say("TODO: Show the settings screen").await?;
+    let guild_id = context.guild_id().unwrap();
+    let data = context.data();
+    let settings = data.settings.get(guild_id).await?;
+
+    let description = fields(data, &settings)
+        .await?
+        .iter()
+        .map(|(name, value)| format_field(name, value))
+        .collect::<Vec<String>>()
+        .join("\n");
+
+    context
+        .send(|message| {
+            message.embed(|embed| {
+                embed
+                    .title("Settings")
+                    .description(description)
+                    .color(responses::Color::Setting.to_color())
+            })
+        })
+        .await?;
+
     Ok(())
 }
 
@@ -49,27 +173,155 @@ async fn dj_role(
     context: Context<'_>,
     #[description = "Selected role"] role: serenity::Role,
 ) -> Result<(), Error> {
-    update_value(context, DJ_ID, &role.id).await;
-    Ok(())
+    update_value(context, DJ_ROLE, Setting::DjRole(Some(role.id))).await
 }
 
 /// Put the bot into DJ only mode
 #[poise::command(slash_command)]
 async fn dj_only(context: Context<'_>, boolean: bool) -> Result<(), Error> {
-    update_value(context, DJ_ONLY, &boolean).await;
-    Ok(())
+    update_value(context, DJ_ONLY, Setting::DjOnly(boolean)).await
 }
 
 /// Give every user in the server DJ permissions with the DJ role
 #[poise::command(slash_command)]
 async fn everyone_dj(context: Context<'_>, boolean: bool) -> Result<(), Error> {
-    update_value(context, EVERYONE_DJ, &boolean).await;
-    Ok(())
+    update_value(context, EVERYONE_DJ, Setting::EveryoneDj(boolean)).await
+}
+
+/// Treat /skip commands issued by DJs as if they were forceskips
+#[poise::command(slash_command)]
+async fn dj_skip_is_forceskip(context: Context<'_>, boolean: bool) -> Result<(), Error> {
+    update_value(context, DJ_SKIP_IS_FORCESKIP, Setting::DjSkipIsForceskip(boolean)).await
 }
 
 /// Set whether the bot should send a now playing message for songs as they come up in the queue
 #[poise::command(slash_command)]
 async fn announce_songs(context: Context<'_>, boolean: bool) -> Result<(), Error> {
-    update_value(context, ANNOUNCE_SONGS, &boolean).await;
-    Ok(())
+    update_value(context, ANNOUNCE_SONGS, Setting::AnnounceSongs(boolean)).await
+}
+
+/// Set the channel songs are announced in, instead of the channel the bot was summoned from
+#[poise::command(slash_command)]
+async fn announce_channel(
+    context: Context<'_>,
+    #[description = "Selected channel, leave empty to announce in the channel the bot was summoned from"]
+    channel: Option<serenity::GuildChannel>,
+) -> Result<(), Error> {
+    let channel_id = channel.map(|channel| channel.id);
+    update_value(context, ANNOUNCE_CHANNEL, Setting::AnnounceChannel(channel_id)).await
+}
+
+/// Set the percentage of listeners that need to vote before a track is skipped
+#[poise::command(slash_command)]
+async fn skip_threshold(
+    context: Context<'_>,
+    #[description = "Percentage of listeners required to skip"]
+    #[min = 1]
+    #[max = 100]
+    percent: u64,
+) -> Result<(), Error> {
+    update_value(context, SKIP_THRESHOLD, Setting::SkipThreshold(percent as i64)).await
+}
+
+/// Set the volume the bot starts at when it joins a voice channel
+#[poise::command(slash_command)]
+async fn default_volume(
+    context: Context<'_>,
+    #[description = "Volume as a percentage, capped by the maximum volume"]
+    #[min = 0]
+    #[max = 1000]
+    percent: u64,
+) -> Result<(), Error> {
+    update_value(context, DEFAULT_VOLUME, Setting::DefaultVolume(percent as i64)).await
+}
+
+/// Set the highest volume that can be set with /volume
+#[poise::command(slash_command)]
+async fn max_volume(
+    context: Context<'_>,
+    #[description = "Volume as a percentage"]
+    #[min = 0]
+    #[max = 1000]
+    percent: u64,
+) -> Result<(), Error> {
+    let guild_id = context.guild_id().unwrap();
+
+    // A player that's already connected could otherwise stay louder than the new maximum until the bot leaves
+    if let Some(player_context) = context.data().lavalink.get_player_context(guild_id) {
+        if player_context.get_player().await?.volume as u64 > percent {
+            player_context.set_volume(percent as u16).await?;
+        }
+    }
+
+    update_value(context, MAX_VOLUME, Setting::MaxVolume(percent as i64)).await
+}
+
+/// Set the EQ profile applied whenever the bot joins a voice channel
+#[poise::command(slash_command)]
+async fn default_eq_profile(
+    context: Context<'_>,
+    #[description = "Name of the profile, leave empty to not apply one"] name: Option<String>,
+) -> Result<(), Error> {
+    eq::set_default(context, name).await
+}
+
+/// Stores the guild's default EQ profile and confirms it like the other settings, for `/eq default` as well.
+pub async fn update_default_eq_profile(context: Context<'_>, profile_id: Option<i64>) -> Result<(), Error> {
+    update_value(context, DEFAULT_EQ_PROFILE, Setting::DefaultEqProfile(profile_id)).await
+}
+
+/// Set whether playlists queued with /play are added in a random order
+#[poise::command(slash_command)]
+async fn shuffle_playlists(context: Context<'_>, boolean: bool) -> Result<(), Error> {
+    update_value(context, SHUFFLE_PLAYLISTS, Setting::ShufflePlaylists(boolean)).await
+}
+
+/// Set whether tracks queued with /play take turns between the people that queued them, instead of going to the end
+#[poise::command(slash_command)]
+async fn fair_queue(context: Context<'_>, boolean: bool) -> Result<(), Error> {
+    update_value(context, FAIR_QUEUE, Setting::FairQueue(boolean)).await
+}
+
+/// Set whether tracks queued by people that leave the voice channel are removed automatically
+#[poise::command(slash_command)]
+async fn auto_absent_cleanup(context: Context<'_>, boolean: bool) -> Result<(), Error> {
+    update_value(context, AUTO_ABSENT_CLEANUP, Setting::AutoAbsentCleanup(boolean)).await
+}
+
+/// Set whether the bot follows the first person to join its voice channel when they move to another channel
+#[poise::command(slash_command)]
+async fn follow_first_listener(context: Context<'_>, boolean: bool) -> Result<(), Error> {
+    update_value(context, FOLLOW_FIRST_LISTENER, Setting::FollowFirstListener(boolean)).await
+}
+
+/// Set whether sponsored segments of a category are skipped automatically, using SponsorBlock
+#[poise::command(slash_command)]
+async fn sponsorblock(
+    context: Context<'_>,
+    #[description = "Category of segments"] category: SponsorBlockCategory,
+    #[description = "Whether segments in the category are skipped"] boolean: bool,
+) -> Result<(), Error> {
+    let guild_id = context.guild_id().unwrap();
+    let data = context.data();
+
+    let bitmask = data.settings.get(guild_id).await?.sponsorblock_categories;
+    let bitmask = match boolean {
+        true => bitmask | category.bit(),
+        false => bitmask & !category.bit(),
+    };
+
+    // The player that's already connected keeps skipping the old categories otherwise, so it's updated before the
+    // change is stored and confirmed
+    if data.lavalink.get_player_context(guild_id).is_some() {
+        let categories = SponsorBlockCategory::from_bitmask(bitmask);
+        sponsorblock::set_categories(&data.lavalink, guild_id, &categories).await?;
+    }
+
+    update_value(context, SPONSORBLOCK, Setting::SponsorBlockCategories(bitmask)).await
+}
+
+/// Set whether the bot sends a message whenever SponsorBlock skips a segment
+#[poise::command(slash_command)]
+async fn announce_sponsorblock(context: Context<'_>, boolean: bool) -> Result<(), Error> {
+    update_value(context, ANNOUNCE_SPONSORBLOCK, Setting::AnnounceSponsorBlock(boolean)).await
 }
@@ -0,0 +1,72 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+};
+
+use poise::serenity_prelude::GuildId;
+use tokio::sync::Mutex;
+
+use super::{DatabaseManager, GuildSettings, Setting};
+
+/// Keeps every guild's settings in memory after they're first read, so checking them doesn't cost a round trip.
+///
+/// Settings have to be changed through the cache so that it stays in sync with the database.
+#[derive(Clone)]
+pub struct SettingsCache {
+    database: DatabaseManager,
+    settings: Arc<RwLock<HashMap<GuildId, GuildSettings>>>,
+    /// Held while writing to the cache, for as long as it takes to get the value from the database. Otherwise settings
+    /// loaded before an update could be cached after it, undoing it.
+    writing: Arc<Mutex<()>>,
+}
+
+impl SettingsCache {
+    pub fn new(database: DatabaseManager) -> Self {
+        Self {
+            database,
+            settings: Arc::new(RwLock::new(HashMap::new())),
+            writing: Arc::new(Mutex::new(())),
+        }
+    }
+
+    /// Returns the settings of a guild, loading them from the database if they aren't cached yet.
+    pub async fn get(&self, guild_id: GuildId) -> Result<GuildSettings, sqlx::Error> {
+        let cached = self.settings.read().unwrap().get(&guild_id).cloned();
+
+        if let Some(settings) = cached {
+            return Ok(settings);
+        }
+
+        let _writing = self.writing.lock().await;
+
+        // They could have been loaded while waiting for the lock
+        let cached = self.settings.read().unwrap().get(&guild_id).cloned();
+
+        if let Some(settings) = cached {
+            return Ok(settings);
+        }
+
+        let settings = self.database.guild_settings(guild_id).await?;
+
+        self.settings
+            .write()
+            .unwrap()
+            .insert(guild_id, settings.clone());
+
+        Ok(settings)
+    }
+
+    /// Writes a new value for one of a guild's settings to the database, then to the cache.
+    pub async fn update(&self, guild_id: GuildId, setting: Setting) -> Result<(), sqlx::Error> {
+        let _writing = self.writing.lock().await;
+
+        self.database.update_setting(guild_id, &setting).await?;
+
+        let mut settings = self.settings.write().unwrap();
+        if let Some(cached) = settings.get_mut(&guild_id) {
+            setting.apply(cached);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,395 @@
+pub mod cache;
+#[cfg(feature = "mysql")]
+pub mod mysql;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+
+use std::sync::Arc;
+
+use lavalink_rs::model::{player::Filters, track::TrackData};
+use poise::serenity_prelude::{async_trait, ChannelId, GuildId, RoleId, UserId};
+use sqlx::{migrate::MigrateError, FromRow};
+
+use crate::Error;
+
+/// A place the bot's data can be stored, each supported database has its own implementation.
+///
+/// Statements and migrations are written per backend, but everything outside of this module only ever sees the
+/// types defined here.
+#[async_trait]
+pub trait Storage: Send + Sync {
+    /// Brings the database schema up to date by applying any migrations that haven't been run yet.
+    async fn migrate(&self) -> Result<(), MigrateError>;
+
+    /// Returns the version of the newest migration applied to the database, or 0 if none have been.
+    async fn schema_version(&self) -> Result<i64, sqlx::Error>;
+
+    /// Returns the settings of a guild, or the defaults if the guild hasn't changed any.
+    async fn guild_settings(&self, guild_id: GuildId) -> Result<GuildSettings, sqlx::Error>;
+
+    /// Stores a new value for one of a guild's settings, populating the guild's row with defaults first if needed.
+    async fn update_setting(&self, guild_id: GuildId, setting: &Setting) -> Result<(), sqlx::Error>;
+
+    /// Returns a guild's favorites in the order they were added.
+    async fn favorites(&self, guild_id: GuildId) -> Result<Vec<Favorite>, sqlx::Error>;
+
+    async fn add_favorite(&self, guild_id: GuildId, added_by: UserId, track: &str) -> Result<(), sqlx::Error>;
+
+    /// Returns false if the guild has no favorite with the id.
+    async fn remove_favorite(&self, guild_id: GuildId, id: i64) -> Result<bool, sqlx::Error>;
+
+    /// Returns a guild's scheduled tracks, the soonest first.
+    async fn scheduled_tracks(&self, guild_id: GuildId) -> Result<Vec<ScheduledTrack>, sqlx::Error>;
+
+    /// Returns the scheduled tracks of every guild that are due to play at or before the unix timestamp.
+    async fn due_scheduled_tracks(&self, now: i64) -> Result<Vec<ScheduledTrack>, sqlx::Error>;
+
+    async fn add_scheduled_track(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+        requested_by: UserId,
+        query: &str,
+        play_at: i64,
+    ) -> Result<(), sqlx::Error>;
+
+    /// Returns false if the guild has no scheduled track with the id.
+    async fn remove_scheduled_track(&self, guild_id: GuildId, id: i64) -> Result<bool, sqlx::Error>;
+
+    /// Returns a guild's EQ profiles sorted by name.
+    async fn eq_profiles(&self, guild_id: GuildId) -> Result<Vec<EqProfile>, sqlx::Error>;
+
+    /// Saves an EQ profile, replacing the filters of the guild's profile with the same name if there is one.
+    async fn save_eq_profile(&self, guild_id: GuildId, name: &str, filters: &str) -> Result<(), sqlx::Error>;
+
+    /// Returns false if the guild has no EQ profile with the name.
+    async fn remove_eq_profile(&self, guild_id: GuildId, name: &str) -> Result<bool, sqlx::Error>;
+}
+
+#[derive(Clone)]
+pub struct DatabaseManager {
+    storage: Arc<dyn Storage>,
+}
+
+/// A guild's row in the `guild_settings` table.
+///
+/// Ids are stored as signed integers, with 0 meaning that the role or channel hasn't been set.
+#[derive(Clone, Debug, FromRow)]
+pub struct GuildSettings {
+    pub guild_id: i64,
+    pub dj_id: i64,
+    pub dj_only: bool,
+    pub announce_songs: bool,
+    pub everyone_dj: bool,
+    pub skip_threshold: i64,
+    pub announce_channel_id: i64,
+    pub auto_absent_cleanup: bool,
+    pub follow_first_listener: bool,
+    /// A bitmask of the SponsorBlock categories that are skipped, see `SponsorBlockCategory`.
+    pub sponsorblock_categories: i64,
+    pub announce_sponsorblock: bool,
+    pub dj_skip_is_forceskip: bool,
+    /// The volume new players start at, as a percentage.
+    pub default_volume: i64,
+    /// The highest volume /volume accepts, as a percentage.
+    pub max_volume: i64,
+    /// The id of the EQ profile applied to new players, 0 if there isn't one.
+    pub default_eq_profile_id: i64,
+    pub shuffle_playlists: bool,
+    pub fair_queue: bool,
+}
+
+impl GuildSettings {
+    /// The settings of a guild that hasn't changed anything yet.
+    pub fn new(guild_id: GuildId) -> Self {
+        Self {
+            guild_id: guild_id.0 as i64,
+            dj_id: 0,
+            dj_only: false,
+            announce_songs: false,
+            everyone_dj: false,
+            skip_threshold: 50,
+            announce_channel_id: 0,
+            auto_absent_cleanup: false,
+            follow_first_listener: false,
+            sponsorblock_categories: 0,
+            announce_sponsorblock: false,
+            dj_skip_is_forceskip: false,
+            default_volume: 100,
+            max_volume: 1000,
+            default_eq_profile_id: 0,
+            shuffle_playlists: false,
+            fair_queue: false,
+        }
+    }
+
+    pub fn dj_role(&self) -> Option<RoleId> {
+        (self.dj_id != 0).then(|| RoleId(self.dj_id as u64))
+    }
+
+    pub fn announce_channel(&self) -> Option<ChannelId> {
+        (self.announce_channel_id != 0).then(|| ChannelId(self.announce_channel_id as u64))
+    }
+}
+
+/// A track saved to a guild's favorites.
+#[derive(Clone, Debug, FromRow)]
+pub struct Favorite {
+    pub id: i64,
+    pub guild_id: i64,
+    pub added_by: i64,
+    /// The track's data serialized as json, including the encoded track, so it can be played without loading it again.
+    pub track: String,
+}
+
+impl Favorite {
+    pub fn track(&self) -> Result<TrackData, serde_json::Error> {
+        serde_json::from_str(&self.track)
+    }
+
+    pub fn added_by(&self) -> UserId {
+        UserId(self.added_by as u64)
+    }
+}
+
+/// A track that will be queued at a later time.
+#[derive(Clone, Debug, FromRow)]
+pub struct ScheduledTrack {
+    pub id: i64,
+    pub guild_id: i64,
+    /// The text channel it was scheduled from.
+    pub channel_id: i64,
+    pub requested_by: i64,
+    /// The search term or url the track is loaded from once it's due.
+    pub query: String,
+    /// When the track is due, as a unix timestamp.
+    pub play_at: i64,
+}
+
+impl ScheduledTrack {
+    pub fn guild_id(&self) -> GuildId {
+        GuildId(self.guild_id as u64)
+    }
+
+    pub fn channel_id(&self) -> ChannelId {
+        ChannelId(self.channel_id as u64)
+    }
+
+    pub fn requested_by(&self) -> UserId {
+        UserId(self.requested_by as u64)
+    }
+}
+
+/// A named set of filters saved by a guild.
+#[derive(Clone, Debug, FromRow)]
+pub struct EqProfile {
+    pub id: i64,
+    pub guild_id: i64,
+    pub name: String,
+    /// The filters serialized as json, in the format Lavalink accepts them.
+    pub filters: String,
+}
+
+impl EqProfile {
+    pub fn filters(&self) -> Result<Filters, serde_json::Error> {
+        serde_json::from_str(&self.filters)
+    }
+}
+
+/// A new value for one of the columns of the `guild_settings` table.
+pub enum Setting {
+    DjRole(Option<RoleId>),
+    DjOnly(bool),
+    AnnounceSongs(bool),
+    EveryoneDj(bool),
+    SkipThreshold(i64),
+    AnnounceChannel(Option<ChannelId>),
+    AutoAbsentCleanup(bool),
+    FollowFirstListener(bool),
+    SponsorBlockCategories(i64),
+    AnnounceSponsorBlock(bool),
+    DjSkipIsForceskip(bool),
+    DefaultVolume(i64),
+    MaxVolume(i64),
+    DefaultEqProfile(Option<i64>),
+    ShufflePlaylists(bool),
+    FairQueue(bool),
+}
+
+/// The value of a setting, as it's bound to a statement.
+enum SettingValue {
+    Integer(i64),
+    Bool(bool),
+}
+
+impl Setting {
+    /// Returns the statement that updates the setting's column, which binds the new value followed by the guild id.
+    ///
+    /// The statements are plain enough to be shared between every backend.
+    fn update_statement(&self) -> &'static str {
+        use Setting::*;
+        match self {
+            DjRole(_) => "UPDATE guild_settings SET dj_id = ? WHERE guild_id = ?",
+            DjOnly(_) => "UPDATE guild_settings SET dj_only = ? WHERE guild_id = ?",
+            AnnounceSongs(_) => "UPDATE guild_settings SET announce_songs = ? WHERE guild_id = ?",
+            EveryoneDj(_) => "UPDATE guild_settings SET everyone_dj = ? WHERE guild_id = ?",
+            SkipThreshold(_) => "UPDATE guild_settings SET skip_threshold = ? WHERE guild_id = ?",
+            AnnounceChannel(_) => "UPDATE guild_settings SET announce_channel_id = ? WHERE guild_id = ?",
+            AutoAbsentCleanup(_) => "UPDATE guild_settings SET auto_absent_cleanup = ? WHERE guild_id = ?",
+            FollowFirstListener(_) => "UPDATE guild_settings SET follow_first_listener = ? WHERE guild_id = ?",
+            SponsorBlockCategories(_) => "UPDATE guild_settings SET sponsorblock_categories = ? WHERE guild_id = ?",
+            AnnounceSponsorBlock(_) => "UPDATE guild_settings SET announce_sponsorblock = ? WHERE guild_id = ?",
+            DjSkipIsForceskip(_) => "UPDATE guild_settings SET dj_skip_is_forceskip = ? WHERE guild_id = ?",
+            DefaultVolume(_) => "UPDATE guild_settings SET default_volume = ? WHERE guild_id = ?",
+            MaxVolume(_) => "UPDATE guild_settings SET max_volume = ? WHERE guild_id = ?",
+            DefaultEqProfile(_) => "UPDATE guild_settings SET default_eq_profile_id = ? WHERE guild_id = ?",
+            ShufflePlaylists(_) => "UPDATE guild_settings SET shuffle_playlists = ? WHERE guild_id = ?",
+            FairQueue(_) => "UPDATE guild_settings SET fair_queue = ? WHERE guild_id = ?",
+        }
+    }
+
+    /// Applies the new value to a guild's settings that have already been loaded.
+    pub fn apply(&self, settings: &mut GuildSettings) {
+        use Setting::*;
+        match self {
+            DjRole(role) => settings.dj_id = role.map_or(0, |role| role.0 as i64),
+            DjOnly(value) => settings.dj_only = *value,
+            AnnounceSongs(value) => settings.announce_songs = *value,
+            EveryoneDj(value) => settings.everyone_dj = *value,
+            SkipThreshold(value) => settings.skip_threshold = *value,
+            AnnounceChannel(channel) => {
+                settings.announce_channel_id = channel.map_or(0, |channel| channel.0 as i64)
+            }
+            AutoAbsentCleanup(value) => settings.auto_absent_cleanup = *value,
+            FollowFirstListener(value) => settings.follow_first_listener = *value,
+            SponsorBlockCategories(value) => settings.sponsorblock_categories = *value,
+            AnnounceSponsorBlock(value) => settings.announce_sponsorblock = *value,
+            DjSkipIsForceskip(value) => settings.dj_skip_is_forceskip = *value,
+            DefaultVolume(value) => settings.default_volume = *value,
+            MaxVolume(value) => settings.max_volume = *value,
+            DefaultEqProfile(id) => settings.default_eq_profile_id = id.unwrap_or(0),
+            ShufflePlaylists(value) => settings.shuffle_playlists = *value,
+            FairQueue(value) => settings.fair_queue = *value,
+        }
+    }
+
+    fn value(&self) -> SettingValue {
+        use Setting::*;
+        match self {
+            DjRole(role) => SettingValue::Integer(role.map_or(0, |role| role.0 as i64)),
+            DjOnly(value)
+            | AnnounceSongs(value)
+            | EveryoneDj(value)
+            | AutoAbsentCleanup(value)
+            | FollowFirstListener(value)
+            | AnnounceSponsorBlock(value)
+            | DjSkipIsForceskip(value)
+            | ShufflePlaylists(value)
+            | FairQueue(value) => SettingValue::Bool(*value),
+            SkipThreshold(value)
+            | SponsorBlockCategories(value)
+            | DefaultVolume(value)
+            | MaxVolume(value) => SettingValue::Integer(*value),
+            DefaultEqProfile(id) => SettingValue::Integer(id.unwrap_or(0)),
+            AnnounceChannel(channel) => {
+                SettingValue::Integer(channel.map_or(0, |channel| channel.0 as i64))
+            }
+        }
+    }
+}
+
+impl DatabaseManager {
+    /// Connects to the database at the given url, picking the backend from the url's scheme.
+    ///
+    /// `mysql://` urls need the `mysql` feature and `sqlite:` urls need the `sqlite` feature.
+    pub async fn connect(url: &str) -> Result<Self, sqlx::Error> {
+        let storage: Arc<dyn Storage> = match url.split(':').next() {
+            #[cfg(feature = "mysql")]
+            Some("mysql") => Arc::new(mysql::MySqlStorage::connect(url).await?),
+            #[cfg(feature = "sqlite")]
+            Some("sqlite") => Arc::new(sqlite::SqliteStorage::connect(url).await?),
+            _ => {
+                return Err(sqlx::Error::Configuration(
+                    "Unsupported database url scheme, is the backend's feature enabled?".into(),
+                ))
+            }
+        };
+
+        Ok(Self { storage })
+    }
+
+    pub fn new(storage: impl Storage + 'static) -> Self {
+        Self {
+            storage: Arc::new(storage),
+        }
+    }
+
+    pub async fn migrate(&self) -> Result<(), MigrateError> {
+        self.storage.migrate().await
+    }
+
+    pub async fn schema_version(&self) -> Result<i64, sqlx::Error> {
+        self.storage.schema_version().await
+    }
+
+    pub async fn guild_settings(&self, guild_id: GuildId) -> Result<GuildSettings, sqlx::Error> {
+        self.storage.guild_settings(guild_id).await
+    }
+
+    pub async fn update_setting(&self, guild_id: GuildId, setting: &Setting) -> Result<(), sqlx::Error> {
+        self.storage.update_setting(guild_id, setting).await
+    }
+
+    pub async fn favorites(&self, guild_id: GuildId) -> Result<Vec<Favorite>, sqlx::Error> {
+        self.storage.favorites(guild_id).await
+    }
+
+    pub async fn add_favorite(&self, guild_id: GuildId, added_by: UserId, track: &TrackData) -> Result<(), Error> {
+        let track = serde_json::to_string(track)?;
+        self.storage.add_favorite(guild_id, added_by, &track).await?;
+        Ok(())
+    }
+
+    pub async fn remove_favorite(&self, guild_id: GuildId, id: i64) -> Result<bool, sqlx::Error> {
+        self.storage.remove_favorite(guild_id, id).await
+    }
+
+    pub async fn scheduled_tracks(&self, guild_id: GuildId) -> Result<Vec<ScheduledTrack>, sqlx::Error> {
+        self.storage.scheduled_tracks(guild_id).await
+    }
+
+    pub async fn due_scheduled_tracks(&self, now: i64) -> Result<Vec<ScheduledTrack>, sqlx::Error> {
+        self.storage.due_scheduled_tracks(now).await
+    }
+
+    pub async fn add_scheduled_track(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+        requested_by: UserId,
+        query: &str,
+        play_at: i64,
+    ) -> Result<(), sqlx::Error> {
+        self.storage
+            .add_scheduled_track(guild_id, channel_id, requested_by, query, play_at)
+            .await
+    }
+
+    pub async fn remove_scheduled_track(&self, guild_id: GuildId, id: i64) -> Result<bool, sqlx::Error> {
+        self.storage.remove_scheduled_track(guild_id, id).await
+    }
+
+    pub async fn eq_profiles(&self, guild_id: GuildId) -> Result<Vec<EqProfile>, sqlx::Error> {
+        self.storage.eq_profiles(guild_id).await
+    }
+
+    pub async fn save_eq_profile(&self, guild_id: GuildId, name: &str, filters: &Filters) -> Result<(), Error> {
+        let filters = serde_json::to_string(filters)?;
+        self.storage.save_eq_profile(guild_id, name, &filters).await?;
+        Ok(())
+    }
+
+    pub async fn remove_eq_profile(&self, guild_id: GuildId, name: &str) -> Result<bool, sqlx::Error> {
+        self.storage.remove_eq_profile(guild_id, name).await
+    }
+}
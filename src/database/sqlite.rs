@@ -0,0 +1,183 @@
+use std::str::FromStr;
+
+use poise::serenity_prelude::{async_trait, ChannelId, GuildId, UserId};
+use sqlx::{
+    migrate::{MigrateError, Migrator},
+    sqlite::{SqliteConnectOptions, SqlitePoolOptions},
+    ConnectOptions, SqlitePool,
+};
+
+use super::{EqProfile, Favorite, GuildSettings, ScheduledTrack, Setting, SettingValue, Storage};
+
+static MIGRATOR: Migrator = sqlx::migrate!("./migrations/sqlite");
+
+pub struct SqliteStorage {
+    pool: SqlitePool,
+}
+
+impl SqliteStorage {
+    /// Connects to the database file at the url, creating it if it doesn't exist yet.
+    pub async fn connect(url: &str) -> Result<Self, sqlx::Error> {
+        let options = SqliteConnectOptions::from_str(url)?
+            .create_if_missing(true)
+            .disable_statement_logging();
+
+        let pool = SqlitePoolOptions::new()
+            .max_connections(5)
+            .connect_with(options)
+            .await?;
+
+        Ok(Self { pool })
+    }
+}
+
+#[async_trait]
+impl Storage for SqliteStorage {
+    /// Applied migrations are tracked by sqlx in the `_sqlx_migrations` table. Schema changes, like new settings
+    /// columns or tables, go in a new file in `migrations/sqlite/` rather than editing an existing one.
+    async fn migrate(&self) -> Result<(), MigrateError> {
+        MIGRATOR.run(&self.pool).await
+    }
+
+    async fn schema_version(&self) -> Result<i64, sqlx::Error> {
+        let (version,): (Option<i64>,) =
+            sqlx::query_as("SELECT MAX(version) FROM _sqlx_migrations WHERE success")
+                .fetch_one(&self.pool)
+                .await?;
+
+        Ok(version.unwrap_or(0))
+    }
+
+    async fn guild_settings(&self, guild_id: GuildId) -> Result<GuildSettings, sqlx::Error> {
+        let settings: Option<GuildSettings> =
+            sqlx::query_as("SELECT * FROM guild_settings WHERE guild_id = ?")
+                .bind(guild_id.0 as i64)
+                .fetch_optional(&self.pool)
+                .await?;
+
+        Ok(settings.unwrap_or_else(|| GuildSettings::new(guild_id)))
+    }
+
+    async fn update_setting(&self, guild_id: GuildId, setting: &Setting) -> Result<(), sqlx::Error> {
+        sqlx::query("INSERT OR IGNORE INTO guild_settings (guild_id) VALUES (?)")
+            .bind(guild_id.0 as i64)
+            .execute(&self.pool)
+            .await?;
+
+        let query = sqlx::query(setting.update_statement());
+        let query = match setting.value() {
+            SettingValue::Integer(value) => query.bind(value),
+            SettingValue::Bool(value) => query.bind(value),
+        };
+
+        query.bind(guild_id.0 as i64).execute(&self.pool).await?;
+
+        Ok(())
+    }
+
+    async fn favorites(&self, guild_id: GuildId) -> Result<Vec<Favorite>, sqlx::Error> {
+        sqlx::query_as("SELECT * FROM favorites WHERE guild_id = ? ORDER BY id")
+            .bind(guild_id.0 as i64)
+            .fetch_all(&self.pool)
+            .await
+    }
+
+    async fn add_favorite(&self, guild_id: GuildId, added_by: UserId, track: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("INSERT INTO favorites (guild_id, added_by, track) VALUES (?, ?, ?)")
+            .bind(guild_id.0 as i64)
+            .bind(added_by.0 as i64)
+            .bind(track)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn remove_favorite(&self, guild_id: GuildId, id: i64) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM favorites WHERE guild_id = ? AND id = ?")
+            .bind(guild_id.0 as i64)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn scheduled_tracks(&self, guild_id: GuildId) -> Result<Vec<ScheduledTrack>, sqlx::Error> {
+        sqlx::query_as("SELECT * FROM scheduled_tracks WHERE guild_id = ? ORDER BY play_at")
+            .bind(guild_id.0 as i64)
+            .fetch_all(&self.pool)
+            .await
+    }
+
+    async fn due_scheduled_tracks(&self, now: i64) -> Result<Vec<ScheduledTrack>, sqlx::Error> {
+        sqlx::query_as("SELECT * FROM scheduled_tracks WHERE play_at <= ? ORDER BY play_at")
+            .bind(now)
+            .fetch_all(&self.pool)
+            .await
+    }
+
+    async fn add_scheduled_track(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+        requested_by: UserId,
+        query: &str,
+        play_at: i64,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "INSERT INTO scheduled_tracks (guild_id, channel_id, requested_by, query, play_at)
+                VALUES (?, ?, ?, ?, ?)",
+        )
+        .bind(guild_id.0 as i64)
+        .bind(channel_id.0 as i64)
+        .bind(requested_by.0 as i64)
+        .bind(query)
+        .bind(play_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn remove_scheduled_track(&self, guild_id: GuildId, id: i64) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM scheduled_tracks WHERE guild_id = ? AND id = ?")
+            .bind(guild_id.0 as i64)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn eq_profiles(&self, guild_id: GuildId) -> Result<Vec<EqProfile>, sqlx::Error> {
+        sqlx::query_as("SELECT * FROM eq_profiles WHERE guild_id = ? ORDER BY name")
+            .bind(guild_id.0 as i64)
+            .fetch_all(&self.pool)
+            .await
+    }
+
+    async fn save_eq_profile(&self, guild_id: GuildId, name: &str, filters: &str) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "INSERT INTO eq_profiles (guild_id, name, filters) VALUES (?, ?, ?)
+                ON CONFLICT (guild_id, name) DO UPDATE SET filters = excluded.filters",
+        )
+        .bind(guild_id.0 as i64)
+        .bind(name)
+        .bind(filters)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn remove_eq_profile(&self, guild_id: GuildId, name: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM eq_profiles WHERE guild_id = ? AND name = ?")
+            .bind(guild_id.0 as i64)
+            .bind(name)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -1,10 +1,11 @@
+mod checks;
 mod commands;
 mod database;
 mod responses;
 
-use std::{collections::{HashSet, VecDeque}, env, time::Duration};
+use std::{collections::{HashMap, HashSet, VecDeque}, env, sync::{Arc, Mutex, OnceLock}, time::Duration};
 
-use commands::{music::{play::play, play::play_skip, force_skip::force_skip, reorder::reorder, queue::queue, remove::remove, leave::leave, clear::clear}, settings::settings};
+use commands::{music::{absent_cleanup::{self, absent_cleanup}, favorites::{favorite, play_favorites}, follow, filter::filter, eq::eq, play::play, play::play_skip, play::play_top, play_later::{self, play_later}, force_skip::force_skip, reorder::reorder, queue::queue, remove::remove, remove_dupes::remove_dupes, seek::{seek, rewind, forward}, shuffle::shuffle, volume::volume, leave::leave, clear::clear, now_playing::{self, now_playing}, pause::{pause, resume}, stop::stop, sponsorblock, skip::{skip, SkipVotes}, looping::{self, loop_track, loop_queue, LoopMode}}, settings::settings};
 use hook::hook;
 use lavalink_rs::{
     model::{events, track::TrackData},
@@ -15,18 +16,31 @@ use poise::{
     serenity_prelude::{self as serenity}
 };
 
-use database::DatabaseManager;
+use database::{cache::SettingsCache, DatabaseManager};
 use songbird::SerenityInit;
-use sqlx::ConnectOptions;
-use tracing::info;
+use tracing::{error, info};
 
+#[derive(Clone)]
 pub struct Data {
     database: DatabaseManager,
+    settings: SettingsCache,
     lavalink: LavalinkClient,
+    skip_votes: Arc<Mutex<HashMap<serenity::GuildId, SkipVotes>>>,
+    now_playing_messages: Arc<Mutex<HashMap<serenity::GuildId, serenity::MessageId>>>,
+    loop_modes: Arc<Mutex<HashMap<serenity::GuildId, LoopMode>>>,
+    summon_channels: Arc<Mutex<HashMap<serenity::GuildId, serenity::ChannelId>>>,
+    announcements: Arc<Mutex<HashMap<serenity::GuildId, (serenity::ChannelId, serenity::MessageId)>>>,
+    followed_listeners: Arc<Mutex<HashMap<serenity::GuildId, serenity::UserId>>>,
+    resumed_tracks: Arc<Mutex<HashMap<serenity::GuildId, String>>>,
+    play_later_guilds: Arc<HashSet<serenity::GuildId>>,
+    http: Arc<serenity::Http>,
 } // User data, which is stored and accessible in all command invocations
 type Error = Box<dyn std::error::Error + Send + Sync>;
 type Context<'a> = poise::Context<'a, Data, Error>;
 
+// Lavalink event hooks are only handed the lavalink client, so they get their own handle to the user data.
+static DATA: OnceLock<Data> = OnceLock::new();
+
 #[tokio::main]
 async fn main() {
     // This will load the environment variables located at `./.env`, relative to
@@ -38,15 +52,6 @@ async fn main() {
     let token =
         env::var("DISCORD_TOKEN").expect("Expected a token in the environment (DISCORD_TOKEN)");
 
-    let db_host = &env::var("MYSQL_HOST")
-        .expect("Expected the database host in the environment (MYSQL_HOST)");
-    let username = &env::var("MYSQL_USERNAME")
-        .expect("Expected the database username in the environment (MYSQL_USERNAME)");
-    let password = &env::var("MYSQL_PASSWORD")
-        .expect("Expected the database password in the environment (MYSQL_PASSWORD)");
-    let database =
-        &env::var("MYSQL_DB").expect("Expected the database name in the environment (MYSQL_DB)");
-
     let lavalink_host = env::var("LAVALINK_HOST")
         .expect("Expected the lavalink host in the environment (LAVALINK_HOST)");
     let lavalink_password = env::var("LAVALINK_PASSWORD")
@@ -54,30 +59,43 @@ async fn main() {
     let lavalink_ssl = env::var("LAVALINK_SSL")
         .expect("Expected the lavalink ssl in the environment (LAVALINK_SSL)");
 
-    // Connect to mysql database
-    let connection_options = sqlx::mysql::MySqlConnectOptions::new();
-
-    let database = DatabaseManager {
-        pool: sqlx::mysql::MySqlPoolOptions::new()
-            .max_connections(5)
-            .connect_with(
-                connection_options
-                    .disable_statement_logging()
-                    .host(db_host)
-                    .username(username)
-                    .password(password)
-                    .database(database),
-            )
-            .await
-            .expect("Couldn't connect to database."),
-    };
+    let play_later_guilds: HashSet<serenity::GuildId> = env::var("PLAY_LATER_GUILDS")
+        .unwrap_or_default()
+        .split(',')
+        .filter(|guild_id| !guild_id.trim().is_empty())
+        .map(|guild_id| {
+            guild_id
+                .trim()
+                .parse()
+                .map(serenity::GuildId)
+                .expect("Expected a comma separated list of guild ids (PLAY_LATER_GUILDS)")
+        })
+        .collect();
+
+    let database = match env::var("DATABASE_URL") {
+        Ok(url) => DatabaseManager::connect(&url).await,
+        Err(_) => connect_from_mysql_env().await,
+    }
+    .expect("Couldn't connect to database.");
+
+    database
+        .migrate()
+        .await
+        .expect("Couldn't apply database migrations.");
+
+    let schema_version = database
+        .schema_version()
+        .await
+        .expect("Couldn't read the database schema version.");
+
+    info!("Database schema is at version {}", schema_version);
 
     let framework = poise::Framework::builder()
         .token(token)
         .client_settings(|c| c.register_songbird())
         .options(poise::FrameworkOptions {
             owners: HashSet::from([serenity::UserId(126179145297166336)]),
-            commands: vec![settings(), play(), play_skip(), force_skip(), reorder(), queue(), remove(), leave(), clear()],
+            commands: vec![settings(), play(), play_skip(), play_top(), skip(), force_skip(), reorder(), queue(), now_playing(), loop_track(), loop_queue(), remove(), remove_dupes(), absent_cleanup(), favorite(), play_favorites(), play_later(), pause(), resume(), stop(), seek(), rewind(), forward(), volume(), filter(), eq(), shuffle(), leave(), clear()],
             // Run before every command
             pre_command: |context| {
                 Box::pin(async move {
@@ -118,6 +136,10 @@ async fn main() {
                 let events = events::Events {
                     raw: Some(raw_event),
                     ready: Some(ready_event),
+                    track_start: Some(track_start_event),
+                    track_end: Some(track_end_event),
+                    track_exception: Some(track_exception_event),
+                    track_stuck: Some(track_stuck_event),
                     ..Default::default()
                 };
 
@@ -136,16 +158,60 @@ async fn main() {
 
                 info!("Bot logged in as {}", ready.user.name);
 
-                Ok(Data {
+                let data = Data {
+                    settings: SettingsCache::new(database.clone()),
                     database,
                     lavalink: client,
-                })
+                    skip_votes: Arc::new(Mutex::new(HashMap::new())),
+                    now_playing_messages: Arc::new(Mutex::new(HashMap::new())),
+                    loop_modes: Arc::new(Mutex::new(HashMap::new())),
+                    summon_channels: Arc::new(Mutex::new(HashMap::new())),
+                    announcements: Arc::new(Mutex::new(HashMap::new())),
+                    followed_listeners: Arc::new(Mutex::new(HashMap::new())),
+                    resumed_tracks: Arc::new(Mutex::new(HashMap::new())),
+                    play_later_guilds: Arc::new(play_later_guilds),
+                    http: context.http.clone(),
+                };
+
+                DATA.set(data.clone()).ok();
+
+                tokio::spawn(play_later::run_scheduler(context.clone(), data.clone()));
+
+                Ok(data)
             })
         });
 
     framework.run().await.unwrap();
 }
 
+/// Connects to the mysql database described by the MYSQL_* variables, for setups from before DATABASE_URL.
+#[cfg(feature = "mysql")]
+async fn connect_from_mysql_env() -> Result<DatabaseManager, sqlx::Error> {
+    let db_host = &env::var("MYSQL_HOST")
+        .expect("Expected the database host in the environment (MYSQL_HOST)");
+    let username = &env::var("MYSQL_USERNAME")
+        .expect("Expected the database username in the environment (MYSQL_USERNAME)");
+    let password = &env::var("MYSQL_PASSWORD")
+        .expect("Expected the database password in the environment (MYSQL_PASSWORD)");
+    let database_name =
+        &env::var("MYSQL_DB").expect("Expected the database name in the environment (MYSQL_DB)");
+
+    let connection_options = sqlx::mysql::MySqlConnectOptions::new()
+        .host(db_host)
+        .username(username)
+        .password(password)
+        .database(database_name);
+
+    let storage = database::mysql::MySqlStorage::connect_with(connection_options).await?;
+
+    Ok(DatabaseManager::new(storage))
+}
+
+#[cfg(not(feature = "mysql"))]
+async fn connect_from_mysql_env() -> Result<DatabaseManager, sqlx::Error> {
+    panic!("Expected a database url in the environment (DATABASE_URL)");
+}
+
 #[hook]
 async fn raw_event(_: LavalinkClient, session_id: String, event: &serde_json::Value) {
     if event["op"].as_str() == Some("event") || event["op"].as_str() == Some("playerUpdate") {
@@ -156,6 +222,16 @@ async fn raw_event(_: LavalinkClient, session_id: String, event: &serde_json::Va
         match event["type"].as_str() {
             Some("WebSocketClosedEvent") => {
 
+            },
+            // Sent by the SponsorBlock plugin
+            Some("SegmentSkipped") => {
+                let Some(data) = DATA.get() else {
+                    return;
+                };
+
+                if let Err(why) = sponsorblock::announce_skip(data, event).await {
+                    error!("Could not announce skipped segment: {}", why);
+                }
             },
             Some(_) => (),
             None => (),
@@ -169,6 +245,73 @@ async fn ready_event(client: LavalinkClient, session_id: String, event: &events:
     info!("Ready event: {:?} -> {:?}", session_id, event);
 }
 
+#[hook]
+async fn track_start_event(client: LavalinkClient, _session_id: String, event: &events::TrackStart) {
+    let Some(data) = DATA.get() else {
+        return;
+    };
+
+    let guild_id = serenity::GuildId(event.guild_id.0);
+
+    // The track was already announced before the player moved to another voice server
+    let resumed_track = data.resumed_tracks.lock().unwrap().remove(&guild_id);
+    if resumed_track.is_some_and(|encoded| encoded == event.track.encoded) {
+        return;
+    }
+
+    let Some(player_context) = client.get_player_context(guild_id) else {
+        return;
+    };
+
+    if let Err(why) = now_playing::announce(data, guild_id, &player_context).await {
+        error!("Could not announce track: {}", why);
+    }
+}
+
+#[hook]
+async fn track_end_event(client: LavalinkClient, _session_id: String, event: &events::TrackEnd) {
+    let Some(data) = DATA.get() else {
+        return;
+    };
+
+    let guild_id = serenity::GuildId(event.guild_id.0);
+
+    // A resumed track that ends never started. Cleanup is the old player being destroyed when moving to another voice
+    // server though, which can come in after the track was resumed on the new one
+    if !matches!(event.reason, events::TrackEndReason::Cleanup) {
+        data.resumed_tracks.lock().unwrap().remove(&guild_id);
+    }
+
+    // Skipped, stopped and replaced tracks aren't looped
+    if !matches!(event.reason, events::TrackEndReason::Finished) {
+        return;
+    }
+
+    let Some(player_context) = client.get_player_context(guild_id) else {
+        return;
+    };
+
+    if let Err(why) = looping::requeue(data, guild_id, &player_context, &event.track).await {
+        error!("Could not requeue looped track: {}", why);
+    }
+}
+
+#[hook]
+async fn track_exception_event(_: LavalinkClient, _session_id: String, event: &events::TrackException) {
+    // A resumed track that fails may never start, and mustn't keep the next track from being announced
+    if let Some(data) = DATA.get() {
+        data.resumed_tracks.lock().unwrap().remove(&serenity::GuildId(event.guild_id.0));
+    }
+}
+
+#[hook]
+async fn track_stuck_event(_: LavalinkClient, _session_id: String, event: &events::TrackStuck) {
+    // Same as for exceptions
+    if let Some(data) = DATA.get() {
+        data.resumed_tracks.lock().unwrap().remove(&serenity::GuildId(event.guild_id.0));
+    }
+}
+
 async fn event_handler(
     context: &serenity::Context,
     event: &Event<'_>,
@@ -200,6 +343,9 @@ async fn event_handler(
             let track_position_queue: Option<(TrackData, u64, VecDeque<TrackInQueue>)>;
             
             let player = player_context.get_player().await?;
+            let paused = player.paused;
+            let volume = player.volume;
+            let filters = player.filters.clone();
             if let Some(track) = player.track {
                 let position = player.state.position;
                 let queue = player_context.get_queue().await?;
@@ -211,21 +357,48 @@ async fn event_handler(
             lava_client.delete_player(guild_id).await?;
             lava_client.create_player_context(guild_id, connection_info).await?;
 
+            if let Err(why) = sponsorblock::apply(data, guild_id).await {
+                error!("Could not set SponsorBlock categories: {}", why);
+            }
+
             let Some(player_context) = lava_client.get_player_context(guild_id) else {
                 return Ok(());
             };
 
             if let Some((track, position, queue)) = track_position_queue {
-                player_context.play_now(&track).await?;
+                data.resumed_tracks.lock().unwrap().insert(guild_id, track.encoded.clone());
+
+                if let Err(why) = player_context.play_now(&track).await {
+                    data.resumed_tracks.lock().unwrap().remove(&guild_id);
+                    return Err(why.into());
+                }
+
                 player_context.set_position(Duration::from_millis(position)).await?;
                 player_context.set_queue(player_context::QueueMessage::Replace(queue))?;
             }
+
+            if paused {
+                player_context.set_pause(true).await?;
+            }
+
+            player_context.set_volume(volume).await?;
+
+            if let Some(filters) = filters {
+                player_context.set_filters(filters).await?;
+            }
         }
 
         Event::VoiceStateUpdate { old: _, new } => {
             let current_user_id = context.cache.current_user_id();
 
             if new.user_id != current_user_id {
+                // Following goes first, so the followed listener's tracks aren't cleaned up as they switch channels
+                follow::follow_first_listener(context, data, new).await?;
+
+                if let Some(guild_id) = new.guild_id {
+                    absent_cleanup::automatic_cleanup(context, data, guild_id).await?;
+                }
+
                 return Ok(());
             }
 
@@ -237,6 +410,9 @@ async fn event_handler(
 
             let guild_id = new.guild_id.unwrap();
             lava_client.delete_player(guild_id).await?;
+
+            data.followed_listeners.lock().unwrap().remove(&guild_id);
+            data.loop_modes.lock().unwrap().remove(&guild_id);
         }
 
         _ => ()
@@ -63,6 +63,11 @@ async fn generic(
 pub enum DefaultMessage {
     Skipped,
     SkippedTo(usize),
+    SkipVote(usize, usize),
+    VoteSkipped(usize, usize),
+    Paused,
+    Resumed,
+    Stopped,
 }
 
 #[async_trait]
@@ -72,6 +77,11 @@ impl Say for DefaultMessage {
         match self {
             Skipped => default(context, "Skipped track.").await?,
             SkippedTo(position) => default(context, format!("Skipped to track {}.", position)).await?,
+            SkipVote(votes, required) => default(context, format!("Voted to skip. **{}/{}** votes needed.", votes, required)).await?,
+            VoteSkipped(votes, required) => default(context, format!("Skipped track. **{}/{}** votes.", votes, required)).await?,
+            Paused => default(context, "Paused playback.").await?,
+            Resumed => default(context, "Resumed playback.").await?,
+            Stopped => default(context, "Stopped playback. Use /resume to continue with the queue.").await?,
         }
 
         Ok(())
@@ -85,6 +95,16 @@ pub enum ErrorMessage {
     BotNotPlaying,
     InvalidSkip,
     InvalidMove,
+    UserNotListening,
+    AlreadyVoted,
+    UserNotDJ,
+    DJOnlyMode,
+    AlreadyPaused,
+    NotPaused,
+    TrackNotSeekable,
+    InvalidTimestamp,
+    InvalidDuration,
+    SeekPastEnd,
 }
 
 #[async_trait]
@@ -98,6 +118,16 @@ impl Say for ErrorMessage {
             BotNotPlaying => error(context, "There are no tracks currently playing.").await?,
             InvalidSkip => error(context, "Tried to skip to an invaild position in the queue.").await?,
             InvalidMove => error(context, "Tried to move a track to/from an invaid position.").await?,
+            UserNotListening => error(context, "You need to be in the same voice channel as the bot to use this command.").await?,
+            AlreadyVoted => error(context, "You have already voted to skip this track.").await?,
+            UserNotDJ => error(context, "You need to be a DJ to use this command.").await?,
+            DJOnlyMode => error(context, "The bot is in DJ only mode, only DJs can play tracks.").await?,
+            AlreadyPaused => error(context, "Playback is already paused.").await?,
+            NotPaused => error(context, "Playback isn't paused.").await?,
+            TrackNotSeekable => error(context, "The currently playing track can't be seeked.").await?,
+            InvalidTimestamp => error(context, "Invalid time specified. Use a timestamp like `1:23`, a duration like `+30s` or `-10s`, or a percentage like `50%`.").await?,
+            InvalidDuration => error(context, "Invalid duration specified. Use a duration like `30s` or `1:00`.").await?,
+            SeekPastEnd => error(context, "Tried to seek past the end of the track.").await?,
         }
 
         Ok(())